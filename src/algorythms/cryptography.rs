@@ -1 +1,4 @@
 pub mod enigma;
+pub mod lorenz;
+pub mod m209;
+pub mod typex;
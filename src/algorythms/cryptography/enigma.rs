@@ -1,88 +1,330 @@
+pub mod analysis;
+mod lampboard;
+mod plugboard;
 mod reflector;
 mod rotor;
+mod settings;
+mod stream;
+mod uhr;
 mod utils;
-use reflector::{Reflector, ReflectorWiring};
-use rotor::{Rotor, RotorWiring};
+pub use plugboard::Plugboard;
+pub use reflector::{Reflector, ReflectorWiring};
+pub use rotor::{step_rotors, Rotor, RotorWiring};
+pub use settings::{parse_dial, parse_rotors, EnigmaSettings, KeySheet, Model, SettingsError};
+pub use stream::{EncryptIter, EnigmaReader, EnigmaWriter};
+pub use uhr::Uhr;
+pub use utils::Alphabet;
 
-use std::{cell::RefCell, rc::Rc};
+/// the way a key press takes through the machine, as the symbol leaving each part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub key: char,
+    /// the name of each part in the order the signal passes it, with the symbol it puts out.
+    pub stages: Vec<(String, char)>,
+}
+
+impl Trace {
+    /// the lamp lit by the key press.
+    pub fn lamp(&self) -> Option<char> {
+        self.stages.last().map(|(_, lamp)| *lamp)
+    }
+}
+
+/// every model has three pawls, so a fourth (greek) rotor never moves.
+const PAWLS: usize = 3;
 
 pub struct Enigma {
-    rotors: Vec<Rc<RefCell<Rotor>>>,
-    reflector: Rc<RefCell<Reflector>>, // plugboard
+    plugboard: Plugboard,
+    rotors: Vec<Rotor>,
+    reflector: Reflector,
 }
 
 impl Enigma {
-    pub fn new(mut rotors: Vec<RotorWiring>, reflector: ReflectorWiring) -> Enigma {
-        let mut last_rotor = None;
-        rotors.reverse();
-        let mut rotors = rotors
+    /// rotors are listed in the order the signal passes them, so the fastest rotor comes first.
+    pub fn new(rotors: Vec<RotorWiring>, reflector: ReflectorWiring) -> Enigma {
+        Enigma {
+            plugboard: Plugboard::identity(Alphabet::latin()),
+            rotors: rotors.into_iter().map(Rotor::from).collect(),
+            reflector: reflector.into(),
+        }
+    }
+
+    /// builds a machine from rotors and a reflector of any alphabet.
+    /// returns `None` unless all of them share the same alphabet.
+    pub fn from_parts(rotors: Vec<Rotor>, reflector: Reflector) -> Option<Enigma> {
+        if rotors
             .iter()
-            .map(|rotor_definition| {
-                let mut rotor: Rotor = rotor_definition.clone().into();
-                rotor.set_carry_rotor(last_rotor.clone());
-                let rotor = Rc::new(RefCell::new(rotor));
-                last_rotor = Some(rotor.clone());
-                rotor
+            .all(|rotor| rotor.alphabet() == reflector.alphabet())
+        {
+            Some(Enigma {
+                plugboard: Plugboard::identity(reflector.alphabet().clone()),
+                rotors,
+                reflector,
             })
-            .collect::<Vec<Rc<RefCell<Rotor>>>>();
-        rotors.reverse();
-        Enigma {
-            rotors,
-            reflector: Rc::new(RefCell::new(reflector.clone().into())),
+        } else {
+            None
+        }
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        self.reflector.alphabet()
+    }
+
+    /// plugs in a plugboard, or an [`Uhr`] turned into one.
+    /// returns `None` if it was made for a different alphabet.
+    pub fn with_plugboard(mut self, plugboard: Plugboard) -> Option<Enigma> {
+        if plugboard.alphabet() == self.alphabet() {
+            self.plugboard = plugboard;
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// reads one symbol per rotor, left (slowest) to right, and returns them fastest first.
+    fn dial(&self, letters: &str) -> Option<Vec<usize>> {
+        let mut dial = self.alphabet().indices(letters)?;
+        dial.reverse();
+        (dial.len() == self.rotors.len()).then_some(dial)
+    }
+
+    /// sets the letters shown in the rotor windows, read from left (slowest) to right.
+    pub fn set_positions(&mut self, positions: &str) -> Option<()> {
+        let positions = self.dial(positions)?;
+        self.rotors
+            .iter_mut()
+            .zip(positions)
+            .for_each(|(rotor, position)| rotor.set_position(position));
+        Some(())
+    }
+
+    pub fn positions(&self) -> String {
+        self.rotors.iter().rev().map(Rotor::window).collect()
+    }
+
+    /// sets the Ringstellung of every rotor as letters, read from left (slowest) to right.
+    pub fn set_ring_settings(&mut self, ring_settings: &str) -> Option<()> {
+        let ring_settings = self.dial(ring_settings)?;
+        self.rotors
+            .iter_mut()
+            .zip(ring_settings)
+            .for_each(|(rotor, ring_setting)| rotor.set_ring_setting(ring_setting));
+        Some(())
+    }
+
+    /// encodes a single character, stepping the rotors first.
+    /// characters the machine has no key for are dropped without moving the rotors.
+    pub fn encode_char(&mut self, c: char) -> Option<char> {
+        self.trace(c).and_then(|trace| trace.lamp())
+    }
+
+    /// encodes a single character like [`Enigma::encode_char`],
+    /// but keeps the symbol on the wire after every part it passes.
+    pub fn trace(&mut self, c: char) -> Option<Trace> {
+        self.alphabet().index_of(c)?;
+        let moving = self.rotors.len().min(PAWLS);
+        step_rotors(&mut self.rotors[..moving]);
+
+        let mut trace = Trace {
+            key: c,
+            stages: vec![],
+        };
+        let mut signal = self.plugboard.forward(c)?;
+        trace.stages.push(("plugboard".to_string(), signal));
+        // forward
+        for rotor in &self.rotors {
+            signal = rotor.forward(signal)?;
+            trace.stages.push((rotor.name().to_string(), signal));
         }
+        // reflect
+        signal = self.reflector.translate(signal)?;
+        trace
+            .stages
+            .push((self.reflector.name().to_string(), signal));
+        // backward
+        for rotor in self.rotors.iter().rev() {
+            signal = rotor.backward(signal)?;
+            trace.stages.push((rotor.name().to_string(), signal));
+        }
+        signal = self.plugboard.backward(signal)?;
+        trace.stages.push(("plugboard".to_string(), signal));
+        Some(trace)
     }
+
     pub fn encode(&mut self, input: &str) -> String {
-        input.chars().fold("".to_string(), |mut acc, c| {
-            // increment first rotor (and carry to next)
-            if let Some(first) = self.rotors.first() {
-                first.borrow_mut().increment()
-            };
-            println!("forward");
-            // forward
-            let forward_translated = self.rotors.iter().fold(Some(c), |curr, rotor| {
-                curr.and_then(|curr| rotor.borrow().forward(curr))
-            });
-            println!("reflecting");
-            // reflect
-            let reflected =
-                forward_translated.and_then(|curr| self.reflector.borrow().translate(curr));
-
-            println!("backward");
-            // backward
-            let backward_translated = self.rotors.iter().rev().fold(reflected, |curr, rotor| {
-                curr.and_then(|curr| rotor.borrow().backward(curr))
-            });
-
-            if let Some(curr) = backward_translated {
-                acc.push(curr)
-            }
-
-            println!("\n\n");
-            // }
-            acc
-        })
+        self.encrypt_iter(input.chars()).collect()
     }
+
+    /// lazily encodes the given characters, keeping the machine state between items.
+    pub fn encrypt_iter<I>(&mut self, chars: I) -> EncryptIter<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = char>,
+    {
+        EncryptIter::new(self, chars.into_iter())
+    }
+
     pub fn reset(&mut self) {
         todo!()
     }
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use std::vec;
 
     use crate::algorythms::cryptography::enigma::ReflectorWiring;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 
     // use usper::{RotorStream};
-    use super::{Enigma, RotorWiring};
+    use super::{Alphabet, Enigma, Plugboard, Reflector, Rotor, RotorWiring, Uhr};
 
-    #[test]
-    fn enigma() {
-        let mut enigma = Enigma::new(
+    /// a machine with random rotor wirings, notches and positions and a random reflector.
+    pub fn random_enigma(seed: u64, alphabet: Alphabet, rotors: usize) -> Enigma {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let size = alphabet.len();
+        let rotors = (0..rotors)
+            .map(|index| {
+                let mut wiring = (0..size).collect::<Vec<usize>>();
+                wiring.shuffle(&mut rng);
+                let notch = alphabet.nth(rng.gen_range(0..size)).to_string();
+                let mut rotor =
+                    Rotor::from_permutation(wiring, &index.to_string(), alphabet.clone())
+                        .and_then(|rotor| rotor.with_notches(&notch))
+                        .unwrap();
+                rotor.set_position(rng.gen_range(0..size));
+                rotor
+            })
+            .collect();
+        // pair up the contacts, an odd one out is wired to itself
+        let mut contacts = (0..size).collect::<Vec<usize>>();
+        contacts.shuffle(&mut rng);
+        let mut wiring = (0..size).collect::<Vec<usize>>();
+        contacts.chunks_exact(2).for_each(|pair| {
+            wiring[pair[0]] = pair[1];
+            wiring[pair[1]] = pair[0];
+        });
+        let reflector = Reflector::from_permutation(wiring, "random", alphabet).unwrap();
+        Enigma::from_parts(rotors, reflector).unwrap()
+    }
+
+    fn enigma_i_ii_iii() -> Enigma {
+        Enigma::new(
             vec![RotorWiring::Iii, RotorWiring::Ii, RotorWiring::I],
             ReflectorWiring::B,
-        );
-        println!("{}", enigma.encode("a").to_ascii_uppercase());
+        )
+    }
+
+    #[test]
+    fn enigma() {
+        let mut enigma = enigma_i_ii_iii();
+        assert_eq!(enigma.encode("aaaaa").to_ascii_uppercase(), "BDZGO");
+        assert_eq!(enigma.rotors[0].window(), "F");
         println!("{:?}", enigma.rotors);
     }
+
+    #[test]
+    fn decodes_own_output() {
+        let plaintext = "thequickbrownfoxjumpsoverthelazydog".repeat(30);
+        let ciphertext = enigma_i_ii_iii().encode(&plaintext);
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(enigma_i_ii_iii().encode(&ciphertext), plaintext);
+    }
+
+    #[test]
+    fn skips_unknown_characters() {
+        assert_eq!(enigma_i_ii_iii().encode("A a-A.a A"), "bdzgo");
+    }
+
+    #[test]
+    fn encrypt_iter() {
+        let mut enigma = enigma_i_ii_iii();
+        let mut encrypted = enigma.encrypt_iter("aaa".chars()).collect::<String>();
+        encrypted.extend(enigma.encrypt_iter(vec!['a', 'a']));
+        assert_eq!(encrypted, "bdzgo");
+    }
+
+    #[test]
+    fn alphanumeric_round_trips() {
+        let mut rng = StdRng::seed_from_u64(36);
+        let alphabet = Alphabet::alphanumeric();
+        for _ in 0..50 {
+            let seed = rng.gen();
+            let plaintext = (0..rng.gen_range(0..500))
+                .map(|_| alphabet.nth(rng.gen_range(0..alphabet.len())))
+                .collect::<String>();
+            let ciphertext = random_enigma(seed, alphabet.clone(), 3).encode(&plaintext);
+            assert_eq!(ciphertext.len(), plaintext.len());
+            assert_eq!(
+                random_enigma(seed, alphabet.clone(), 3).encode(&ciphertext),
+                plaintext
+            );
+        }
+    }
+
+    #[test]
+    fn mixed_alphabets() {
+        let digits = Alphabet::new("0123456789").unwrap();
+        let rotor = Rotor::with_alphabet("3917460528", "digits", digits).unwrap();
+        assert!(Enigma::from_parts(vec![rotor], ReflectorWiring::B.into()).is_none());
+    }
+
+    #[test]
+    fn ring_settings() {
+        let mut enigma = enigma_i_ii_iii();
+        enigma.set_ring_settings("BBB").unwrap();
+        assert_eq!(enigma.encode("aaaaa"), "ewtyx");
+        assert!(enigma.set_ring_settings("BB").is_none());
+    }
+
+    #[test]
+    fn positions() {
+        let mut enigma = enigma_i_ii_iii();
+        enigma.set_positions("ADU").unwrap();
+        enigma.encode("aaa");
+        assert_eq!(enigma.positions(), "BFX");
+        assert!(enigma.set_positions("AD1").is_none());
+    }
+
+    #[test]
+    fn greek_wheel_stays() {
+        // with the thin reflector B and Beta at A, the M4 behaves like an M3 with reflector B
+        let mut m4 = Enigma::new(
+            vec![
+                RotorWiring::Iii,
+                RotorWiring::Ii,
+                RotorWiring::I,
+                RotorWiring::Beta,
+            ],
+            ReflectorWiring::Bthin,
+        );
+        let plaintext = "vonvonjlooksjhffttteinseinsdreizwoyyqnnsneuninhaltxx".repeat(20);
+        assert_eq!(m4.encode(&plaintext), enigma_i_ii_iii().encode(&plaintext));
+        assert!(m4.positions().starts_with('A'));
+    }
+
+    #[test]
+    fn plugboard() {
+        let plugged = || {
+            enigma_i_ii_iii()
+                .with_plugboard(Plugboard::new("AB ZY").unwrap())
+                .unwrap()
+        };
+        // without cables AAAAA encodes to BDZGO, now B stands in for A and Y for Z
+        assert_eq!(plugged().encode("bbbbb"), "adygo");
+        assert_eq!(plugged().encode("adygo"), "bbbbb");
+        assert!(enigma_i_ii_iii()
+            .with_plugboard(Plugboard::identity(Alphabet::alphanumeric()))
+            .is_none());
+    }
+
+    #[test]
+    fn uhr_decrypts() {
+        let plaintext = "dasoberkommandoderwehrmachtgibtbekannt".repeat(5);
+        for position in 0..40 {
+            let uhr = Uhr::new("AQ BW CE DR FT GZ HU JI KO LP", position).unwrap();
+            let machine = || enigma_i_ii_iii().with_plugboard((&uhr).into()).unwrap();
+            let ciphertext = machine().encode(&plaintext);
+            assert_eq!(machine().encode(&ciphertext), plaintext);
+        }
+    }
 }
@@ -0,0 +1,257 @@
+//! counting tools for the stepping and settings of a machine.
+//! rotors are given fastest first, like everywhere else in the machine.
+
+use super::{step_rotors, ReflectorWiring, Rotor, RotorWiring, PAWLS};
+use std::collections::HashMap;
+
+/// the number of settings of a machine assembled from the given parts that behave differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keyspace {
+    /// rotor orders with their ring settings and starting positions.
+    pub rotor_settings: u128,
+    pub reflectors: u128,
+    pub plugboards: u128,
+}
+
+impl Keyspace {
+    pub fn total(&self) -> u128 {
+        self.rotor_settings * self.reflectors * self.plugboards
+    }
+}
+
+/// the ways to plug `cables` cables into a plugboard with `letters` sockets.
+pub fn plugboard_settings(letters: usize, cables: usize) -> u128 {
+    if cables * 2 > letters {
+        return 0;
+    }
+    // choose the 2n plugged letters, then pair them up: (2n)! / (n! 2^n)
+    let choose = (0..cables * 2).fold(1u128, |acc, i| {
+        acc * (letters - i) as u128 / (i + 1) as u128
+    });
+    let pairings = (1..=cables).fold(1u128, |acc, i| acc * (2 * i - 1) as u128);
+    choose * pairings
+}
+
+/// the smallest shift that maps the notches of `rotor` onto themselves.
+/// positions that far apart step their neighbour at the same moments.
+fn notch_period(rotor: &Rotor) -> usize {
+    let size = rotor.alphabet().len();
+    (1..=size)
+        .find(|shift| {
+            rotor
+                .notches()
+                .iter()
+                .all(|notch| rotor.notches().contains(&((notch + shift) % size)))
+        })
+        .unwrap_or(size)
+}
+
+/// the settings of one rotor order that behave differently.
+/// the ring setting and position of a rotor only matter together, as the offset of its wiring,
+/// unless its notches drive the rotor to its left. then the position relative to the notches
+/// counts as well, which for rotors VI to VIII with two opposite notches only has half the values.
+pub fn rotor_settings(rotors: &[Rotor]) -> u128 {
+    let moving = rotors.len().min(PAWLS);
+    rotors
+        .iter()
+        .enumerate()
+        .map(|(index, rotor)| {
+            let size = rotor.alphabet().len() as u128;
+            if index + 1 < moving {
+                size * notch_period(rotor) as u128
+            } else {
+                size
+            }
+        })
+        .product()
+}
+
+/// the keyspace of an M3 (`greek` empty) or M4 that takes its rotors from `rotors`,
+/// its greek wheel from `greek`, one of `reflectors` and has `cables` plug cables.
+/// no rotor can be used twice in one order.
+pub fn keyspace(
+    rotors: &[RotorWiring],
+    greek: &[RotorWiring],
+    reflectors: &[ReflectorWiring],
+    cables: usize,
+) -> Keyspace {
+    let greek = if greek.is_empty() {
+        vec![None]
+    } else {
+        greek.iter().copied().map(Some).collect()
+    };
+    let mut orders = 0;
+    for fast in rotors {
+        for middle in rotors.iter().filter(|middle| *middle != fast) {
+            for slow in rotors
+                .iter()
+                .filter(|slow| *slow != fast && *slow != middle)
+            {
+                for greek in &greek {
+                    let order = [Some(*fast), Some(*middle), Some(*slow), *greek]
+                        .into_iter()
+                        .flatten()
+                        .map(Rotor::from)
+                        .collect::<Vec<Rotor>>();
+                    orders += rotor_settings(&order);
+                }
+            }
+        }
+    }
+    Keyspace {
+        rotor_settings: orders,
+        reflectors: reflectors.len() as u128,
+        plugboards: plugboard_settings(26, cables),
+    }
+}
+
+/// the windows of the moving rotors visited from their current positions on, fastest first,
+/// until one repeats. returns them along with the step at which the cycle starts.
+fn stepping_cycle(rotors: &[Rotor]) -> (Vec<Vec<usize>>, usize) {
+    let mut rotors = rotors[..rotors.len().min(PAWLS)].to_vec();
+    let mut seen = HashMap::new();
+    let mut visited = vec![];
+    loop {
+        let positions = rotors.iter().map(Rotor::position).collect::<Vec<usize>>();
+        if let Some(start) = seen.get(&positions) {
+            return (visited, *start);
+        }
+        seen.insert(positions.clone(), visited.len());
+        visited.push(positions);
+        step_rotors(&mut rotors);
+    }
+}
+
+/// the number of key presses after which the rotors are back in the same positions.
+/// because of the double step this is 16,900 rather than 17,576 for most rotor orders.
+/// a few positions, like a middle rotor set to its notch by hand, are never reached again;
+/// they lead into the cycle, whose length is returned.
+pub fn period(rotors: &[Rotor]) -> usize {
+    let (visited, start) = stepping_cycle(rotors);
+    visited.len() - start
+}
+
+/// the windows, read left to right, in which the next key press double steps the middle rotor.
+/// only positions on the cycle the rotors run through are listed, in the order they come up.
+pub fn double_steps(rotors: &[Rotor]) -> Vec<String> {
+    let moving = rotors.len().min(PAWLS);
+    if moving < 3 {
+        return vec![];
+    }
+    let (visited, start) = stepping_cycle(rotors);
+    let middle = &rotors[1];
+    visited[start..]
+        .iter()
+        .filter(|positions| middle.notches().contains(&positions[1]))
+        .map(|positions| {
+            positions
+                .iter()
+                .zip(rotors)
+                .rev()
+                .map(|(position, rotor)| rotor.alphabet().nth(*position).to_ascii_uppercase())
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{double_steps, keyspace, period, plugboard_settings, rotor_settings};
+    use crate::algorythms::cryptography::enigma::{
+        step_rotors, ReflectorWiring, Rotor, RotorWiring,
+    };
+
+    fn rotors(wirings: &[RotorWiring]) -> Vec<Rotor> {
+        wirings.iter().copied().map(Rotor::from).collect()
+    }
+
+    #[test]
+    fn periods() {
+        let mut i_ii_iii = rotors(&[RotorWiring::Iii, RotorWiring::Ii, RotorWiring::I]);
+        assert_eq!(period(&i_ii_iii), 16900);
+        // AEA is never reached by stepping, but it runs into the same cycle
+        i_ii_iii[1].set_position(4);
+        assert_eq!(period(&i_ii_iii), 16900);
+
+        // two notches on the fast and middle rotor
+        let vi_vii = rotors(&[RotorWiring::Vi, RotorWiring::Vii, RotorWiring::I]);
+        assert_eq!(period(&vi_vii), 26 * 12 * 26 / 2);
+        // the greek wheel never moves
+        let m4 = rotors(&[
+            RotorWiring::Iii,
+            RotorWiring::Ii,
+            RotorWiring::I,
+            RotorWiring::Beta,
+        ]);
+        assert_eq!(period(&m4), 16900);
+    }
+
+    #[test]
+    fn double_step_positions() {
+        let mut i_ii_iii = rotors(&[RotorWiring::Iii, RotorWiring::Ii, RotorWiring::I]);
+        let positions = double_steps(&i_ii_iii);
+        assert_eq!(positions.len(), 26);
+        assert!(positions.iter().all(|position| position.ends_with("EW")));
+        assert_eq!(positions[0], "AEW");
+
+        // from AEW the next key press moves all three rotors
+        i_ii_iii[1].set_position(4);
+        i_ii_iii[0].set_position(22);
+        step_rotors(&mut i_ii_iii);
+        assert_eq!(
+            i_ii_iii.iter().map(Rotor::position).collect::<Vec<usize>>(),
+            vec![23, 5, 1]
+        );
+        assert!(double_steps(&rotors(&[RotorWiring::I, RotorWiring::Ii])).is_empty());
+    }
+
+    #[test]
+    fn keyspaces() {
+        assert_eq!(plugboard_settings(26, 10), 150_738_274_937_250);
+        assert_eq!(plugboard_settings(26, 0), 1);
+        assert_eq!(plugboard_settings(26, 13), 7_905_853_580_625);
+        assert_eq!(plugboard_settings(26, 14), 0);
+
+        assert_eq!(
+            rotor_settings(&rotors(&[
+                RotorWiring::Iii,
+                RotorWiring::Ii,
+                RotorWiring::I
+            ])),
+            26u128.pow(5)
+        );
+        assert_eq!(
+            rotor_settings(&rotors(&[
+                RotorWiring::Vi,
+                RotorWiring::Vii,
+                RotorWiring::I
+            ])),
+            26 * 13 * 26 * 13 * 26
+        );
+
+        // Enigma I with five rotors, reflector B and ten cables
+        let enigma_i = keyspace(
+            &[
+                RotorWiring::I,
+                RotorWiring::Ii,
+                RotorWiring::Iii,
+                RotorWiring::Iv,
+                RotorWiring::V,
+            ],
+            &[],
+            &[ReflectorWiring::B],
+            10,
+        );
+        assert_eq!(enigma_i.rotor_settings, 60 * 26u128.pow(5));
+        assert_eq!(enigma_i.total(), 107_458_687_327_250_619_360_000);
+
+        let m4 = keyspace(
+            &[RotorWiring::I, RotorWiring::Ii, RotorWiring::Iii],
+            &[RotorWiring::Beta, RotorWiring::Gamma],
+            &[ReflectorWiring::Bthin, ReflectorWiring::Cthin],
+            10,
+        );
+        assert_eq!(m4.rotor_settings, 6 * 2 * 26u128.pow(6));
+        assert_eq!(m4.reflectors, 2);
+    }
+}
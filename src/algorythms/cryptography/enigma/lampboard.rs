@@ -0,0 +1,150 @@
+use super::{Alphabet, Enigma, Trace};
+use terminal_size::{terminal_size, Width};
+
+/// the keys of the Wehrmacht machines, which left out the umlauts of a German typewriter.
+const QWERTZ: [&str; 3] = ["QWERTZUIO", "ASDFGHJK", "PYXCVBNML"];
+
+/// the rows of keys and lamps, QWERTZ for the latin alphabet and rows of ten otherwise.
+fn rows(alphabet: &Alphabet) -> Vec<Vec<char>> {
+    if *alphabet == Alphabet::latin() {
+        QWERTZ.iter().map(|row| row.chars().collect()).collect()
+    } else {
+        (0..alphabet.len())
+            .map(|index| alphabet.nth(index).to_ascii_uppercase())
+            .collect::<Vec<char>>()
+            .chunks(10)
+            .map(<[char]>::to_vec)
+            .collect()
+    }
+}
+
+/// draws one row of keys or lamps, marking `lit` with the given brackets.
+fn draw_row(row: &[char], indent: usize, lit: Option<char>, brackets: (char, char)) -> String {
+    let mut line = " ".repeat(indent);
+    for symbol in row {
+        if Some(*symbol) == lit {
+            line.push(brackets.0);
+            line.push(*symbol);
+            line.push(brackets.1);
+        } else {
+            line.push(' ');
+            line.push(*symbol);
+            line.push(' ');
+        }
+        line.push(' ');
+    }
+    line.trim_end().to_string()
+}
+
+impl Enigma {
+    /// draws the rotor windows, lamp board, keyboard and plugboard.
+    /// with a `trace` the pressed key and the lit lamp are marked and the path of the signal is shown.
+    pub fn fmt(&self, trace: Option<&Trace>) -> String {
+        let mut lines = vec![];
+        let max_width = terminal_size()
+            .map(|(Width(width), _)| width as usize)
+            .unwrap_or(usize::MAX);
+
+        let rotors = self.rotors.iter().rev();
+        lines.push(
+            rotors
+                .clone()
+                .map(|rotor| format!("{:^7}", rotor.name()))
+                .collect::<Vec<String>>()
+                .join(" ")
+                + &format!("   reflector {}", self.reflector.name()),
+        );
+        lines.push(
+            rotors
+                .map(|rotor| format!("{:^7}", format!("[ {} ]", rotor.window())))
+                .collect::<Vec<String>>()
+                .join(" "),
+        );
+        lines.push(String::new());
+
+        let upper = |c: char| c.to_ascii_uppercase();
+        let lamp = trace.and_then(Trace::lamp).map(upper);
+        let key = trace.map(|trace| upper(trace.key));
+        let rows = rows(self.alphabet());
+        lines.push("lamps".to_string());
+        for (indent, row) in rows.iter().enumerate() {
+            lines.push(draw_row(row, 1 + indent % 2 * 2, lamp, ('(', ')')));
+        }
+        lines.push("keys".to_string());
+        for (indent, row) in rows.iter().enumerate() {
+            lines.push(draw_row(row, 1 + indent % 2 * 2, key, ('[', ']')));
+        }
+        lines.push(String::new());
+
+        let plugs = self
+            .plugboard
+            .connections()
+            .iter()
+            .map(|(from, to)| format!("{}{}", upper(*from), upper(*to)))
+            .collect::<Vec<String>>();
+        let separator = if self.plugboard.is_reciprocal() {
+            " "
+        } else {
+            " >"
+        };
+        lines.push(format!("plugs {}", plugs.join(separator)));
+        if let Some(trace) = trace {
+            lines.push(format!(
+                "path  {} > {}",
+                upper(trace.key),
+                trace
+                    .stages
+                    .iter()
+                    .map(|(part, symbol)| format!("{} {}", part, upper(*symbol)))
+                    .collect::<Vec<String>>()
+                    .join(" > ")
+            ));
+        }
+
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(max_width).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{
+        test::random_enigma, Alphabet, Enigma, Plugboard, ReflectorWiring, RotorWiring,
+    };
+
+    #[test]
+    fn lamp_board() {
+        let mut enigma = Enigma::new(
+            vec![RotorWiring::Iii, RotorWiring::Ii, RotorWiring::I],
+            ReflectorWiring::B,
+        )
+        .with_plugboard(Plugboard::new("AB").unwrap())
+        .unwrap();
+        let trace = enigma.trace('b').unwrap();
+        assert_eq!(trace.lamp(), Some('a'));
+        assert_eq!(trace.stages.len(), 9);
+        assert_eq!(trace.stages[4], ("B".to_string(), 's'));
+
+        let board = enigma.fmt(Some(&trace));
+        let lines = board.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[1].split_whitespace().collect::<String>(), "[A][A][B]");
+        assert!(lines[5].contains("(A)"));
+        assert!(lines[10].contains("[B]"));
+        assert_eq!(lines[12], "plugs AB");
+        assert!(lines[13].starts_with("path  B > plugboard A > III C > II D > I F > B S"));
+    }
+
+    #[test]
+    fn other_alphabets() {
+        let mut enigma = random_enigma(3, Alphabet::alphanumeric(), 3);
+        let trace = enigma.trace('7').unwrap();
+        // 36 symbols in four rows of lamps and four rows of keys
+        let board = enigma.fmt(Some(&trace));
+        assert_eq!(board.lines().count(), 16);
+        assert!(board.contains("[7]"));
+        assert_eq!(enigma.fmt(None).lines().count(), 15);
+    }
+}
@@ -0,0 +1,129 @@
+use super::utils::{invert, Alphabet};
+
+/// the Steckerbrett between the keyboard and the entry wheel.
+/// a plain plugboard swaps pairs of letters, an [`Uhr`](super::Uhr) may wire them in any order,
+/// so the way back to the lamps uses the inverse wiring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plugboard {
+    alphabet: Alphabet,
+    wiring: Vec<usize>,
+    inverse: Vec<usize>,
+}
+
+impl Plugboard {
+    /// a plugboard without any cables.
+    pub fn identity(alphabet: Alphabet) -> Plugboard {
+        let wiring = (0..alphabet.len()).collect::<Vec<usize>>();
+        Plugboard {
+            alphabet,
+            inverse: wiring.clone(),
+            wiring,
+        }
+    }
+
+    /// reads cable pairs like `"AB CD EF"`. returns `None` if a pair is not two letters
+    /// or a letter is plugged twice.
+    pub fn new(pairs: &str) -> Option<Plugboard> {
+        Plugboard::with_alphabet(pairs, Alphabet::latin())
+    }
+
+    pub fn with_alphabet(pairs: &str, alphabet: Alphabet) -> Option<Plugboard> {
+        let pairs = Plugboard::parse_pairs(pairs, &alphabet)?;
+        let mut wiring = (0..alphabet.len()).collect::<Vec<usize>>();
+        for (a, b) in pairs {
+            wiring.swap(a, b);
+        }
+        Plugboard::from_permutation(wiring, alphabet)
+    }
+
+    /// parses cable pairs into indices of `alphabet`, making sure no symbol is used twice.
+    pub fn parse_pairs(pairs: &str, alphabet: &Alphabet) -> Option<Vec<(usize, usize)>> {
+        let pairs = pairs
+            .split_whitespace()
+            .map(|pair| match alphabet.indices(pair)?.as_slice() {
+                [a, b] if a != b => Some((*a, *b)),
+                _ => None,
+            })
+            .collect::<Option<Vec<(usize, usize)>>>()?;
+        let mut plugged = pairs
+            .iter()
+            .flat_map(|(a, b)| [*a, *b])
+            .collect::<Vec<usize>>();
+        plugged.sort_unstable();
+        plugged.dedup();
+        (plugged.len() == pairs.len() * 2).then_some(pairs)
+    }
+
+    /// a plugboard sending the letter at contact `i` on to contact `wiring[i]`.
+    pub fn from_permutation(wiring: Vec<usize>, alphabet: Alphabet) -> Option<Plugboard> {
+        if wiring.len() != alphabet.len() {
+            return None;
+        }
+        let inverse = invert(&wiring)?;
+        Some(Plugboard {
+            alphabet,
+            wiring,
+            inverse,
+        })
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// from the keyboard towards the rotors.
+    pub fn forward(&self, input: char) -> Option<char> {
+        self.alphabet
+            .index_of(input)
+            .map(|index| self.alphabet.nth(self.wiring[index]))
+    }
+
+    /// from the rotors towards the lamps.
+    pub fn backward(&self, input: char) -> Option<char> {
+        self.alphabet
+            .index_of(input)
+            .map(|index| self.alphabet.nth(self.inverse[index]))
+    }
+
+    /// whether the plugboard swaps letters in pairs, like cables do.
+    pub fn is_reciprocal(&self) -> bool {
+        self.wiring == self.inverse
+    }
+
+    /// the plugged connections as (keyboard, rotor side) symbols.
+    /// a swapped pair is only listed once if the plugboard is reciprocal.
+    pub fn connections(&self) -> Vec<(char, char)> {
+        let reciprocal = self.is_reciprocal();
+        self.wiring
+            .iter()
+            .enumerate()
+            .filter(|(from, to)| **to != *from && (!reciprocal || *from < **to))
+            .map(|(from, to)| (self.alphabet.nth(from), self.alphabet.nth(*to)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Plugboard;
+
+    #[test]
+    fn swaps_pairs() {
+        let plugboard = Plugboard::new("AB cd").unwrap();
+        assert_eq!(plugboard.forward('a'), Some('b'));
+        assert_eq!(plugboard.forward('B'), Some('a'));
+        assert_eq!(plugboard.backward('d'), Some('c'));
+        assert_eq!(plugboard.forward('e'), Some('e'));
+        assert!(plugboard.is_reciprocal());
+        assert_eq!(plugboard.connections(), vec![('a', 'b'), ('c', 'd')]);
+    }
+
+    #[test]
+    fn rejects_invalid_pairs() {
+        assert!(Plugboard::new("AB BC").is_none());
+        assert!(Plugboard::new("AA").is_none());
+        assert!(Plugboard::new("ABC").is_none());
+        assert!(Plugboard::new("A1").is_none());
+        assert!(Plugboard::new("").is_some());
+    }
+}
@@ -1,26 +1,61 @@
-use super::utils::n_abc;
-use std::fmt::Debug;
-#[derive(Clone)]
+use super::utils::Alphabet;
+use std::{fmt::Debug, str::FromStr};
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReflectorWiring {
     A,
     B,
     C,
-    // Bthin,
-    // Cthin
+    Bthin,
+    Cthin,
+}
+
+impl ReflectorWiring {
+    /// thin reflectors leave room for the greek wheel of the M4.
+    pub fn is_thin(&self) -> bool {
+        matches!(self, ReflectorWiring::Bthin | ReflectorWiring::Cthin)
+    }
 }
 
-impl Into<Reflector> for ReflectorWiring {
-    fn into(self) -> Reflector {
-        match self {
-            Self::A => Reflector::new("EJMZALYXVBWFCRQUONTSPIKHGD", "A").unwrap(),
-            Self::B => Reflector::new("YRUHQSLDPXNGOKMIEBFZCWVJAT", "B").unwrap(),
-            Self::C => Reflector::new("FVPJIAOYEDRZXWGCTKUQSBNMHL", "C").unwrap(),
+impl FromStr for ReflectorWiring {
+    type Err = String;
+
+    /// parses `"A"`, `"B"`, `"C"`, `"B-thin"` or `"C-thin"` (dash optional, any case).
+    fn from_str(name: &str) -> Result<ReflectorWiring, String> {
+        match name
+            .to_ascii_uppercase()
+            .replace(['-', '_', ' '], "")
+            .as_str()
+        {
+            "A" => Ok(ReflectorWiring::A),
+            "B" => Ok(ReflectorWiring::B),
+            "C" => Ok(ReflectorWiring::C),
+            "BTHIN" => Ok(ReflectorWiring::Bthin),
+            "CTHIN" => Ok(ReflectorWiring::Cthin),
+            _ => Err(format!("unknown reflector {}", name)),
         }
     }
 }
 
+impl From<ReflectorWiring> for Reflector {
+    fn from(wiring: ReflectorWiring) -> Reflector {
+        match wiring {
+            ReflectorWiring::A => Reflector::new("EJMZALYXVBWFCRQUONTSPIKHGD", "A").unwrap(),
+            ReflectorWiring::B => Reflector::new("YRUHQSLDPXNGOKMIEBFZCWVJAT", "B").unwrap(),
+            ReflectorWiring::C => Reflector::new("FVPJIAOYEDRZXWGCTKUQSBNMHL", "C").unwrap(),
+            ReflectorWiring::Bthin => {
+                Reflector::new("ENKQAUYWJICOPBLMDXZVFTHRGS", "B-thin").unwrap()
+            }
+            ReflectorWiring::Cthin => {
+                Reflector::new("RDOBJNTKVEHMLFCWZAXGYIPSUQ", "C-thin").unwrap()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Reflector {
-    configuration: [char; 26],
+    alphabet: Alphabet,
+    wiring: Vec<usize>,
     name: String,
     model_name: Option<String>,
     date_introduced: Option<String>,
@@ -51,37 +86,71 @@ impl Debug for Reflector {
 
 impl Reflector {
     pub fn new(wires: &str, name: &str) -> Option<Reflector> {
-        match wires
-            .to_ascii_lowercase()
-            .chars()
-            .collect::<Vec<char>>()
-            .try_into()
-        {
-            Ok(configuration) => Some(Reflector {
-                configuration,
+        Reflector::with_alphabet(wires, name, Alphabet::latin())
+    }
+
+    /// a reflector connecting the symbols of `alphabet` to those in `wires`.
+    /// returns `None` unless the wires pair up the symbols, i.e. map each pair onto each other.
+    pub fn with_alphabet(wires: &str, name: &str, alphabet: Alphabet) -> Option<Reflector> {
+        let wiring = alphabet.indices(wires)?;
+        Reflector::from_permutation(wiring, name, alphabet)
+    }
+
+    /// a reflector wiring contact `i` to contact `wiring[i]`.
+    pub fn from_permutation(
+        wiring: Vec<usize>,
+        name: &str,
+        alphabet: Alphabet,
+    ) -> Option<Reflector> {
+        let pairs_up = wiring.len() == alphabet.len()
+            && wiring
+                .iter()
+                .enumerate()
+                .all(|(contact, wired)| wiring.get(*wired) == Some(&contact));
+        if pairs_up {
+            Some(Reflector {
+                alphabet,
+                wiring,
                 name: name.to_string(),
                 model_name: None,
                 date_introduced: None,
-            }),
-            _ => None,
+            })
+        } else {
+            None
         }
     }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     pub fn translate(&self, input: char) -> Option<char> {
-        let offset = 'a' as u8;
-        let limit = 'z' as u8;
-        let input = input.to_ascii_lowercase() as u8;
+        self.alphabet
+            .index_of(input)
+            .map(|index| self.alphabet.nth(self.wiring[index]))
+    }
+}
 
-        println!(
-            "reflecting {}. {}=>{}",
-            self.name,
-            n_abc((input - offset) as usize),
-            self.configuration[(input - offset) as usize].to_ascii_uppercase(),
-        );
+#[cfg(test)]
+mod test {
+    use super::{Reflector, ReflectorWiring};
+    use crate::algorythms::cryptography::enigma::utils::Alphabet;
 
-        if input >= offset && input <= limit {
-            Some(self.configuration[(input - offset) as usize])
-        } else {
-            None
-        }
+    #[test]
+    fn reflects() {
+        let reflector: Reflector = ReflectorWiring::B.into();
+        assert_eq!(reflector.translate('A'), Some('y'));
+        assert_eq!(reflector.translate('y'), Some('a'));
+        assert_eq!(reflector.translate('1'), None);
+        // A is wired to B, but B to C
+        assert!(Reflector::new("BCAEDGFIHKJMLONQPSRUTWVYXZ", "broken").is_none());
+        assert!(Reflector::with_alphabet("10", "binary", Alphabet::new("01").unwrap()).is_some());
+        assert_eq!("c-thin".parse(), Ok(ReflectorWiring::Cthin));
+        assert_eq!("BThin".parse(), Ok(ReflectorWiring::Bthin));
+        assert!("D".parse::<ReflectorWiring>().is_err());
     }
 }
@@ -1,8 +1,8 @@
-use std::{cell::RefCell, fmt::Debug, rc::Rc};
+use std::{fmt::Debug, str::FromStr};
 
-use super::utils::n_abc;
+use super::utils::{invert, Alphabet};
 
-#[derive(Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RotorWiring {
     Ic,
     Iic,
@@ -10,28 +10,91 @@ pub enum RotorWiring {
     I,
     Ii,
     Iii,
+    Iv,
+    V,
+    Vi,
+    Vii,
+    Viii,
+    /// the thin greek wheels of the M4, which sit next to the reflector and never move.
+    Beta,
+    Gamma,
+}
+
+impl RotorWiring {
+    pub fn is_greek(&self) -> bool {
+        matches!(self, RotorWiring::Beta | RotorWiring::Gamma)
+    }
+}
+
+impl FromStr for RotorWiring {
+    type Err = String;
+
+    /// parses the name stamped on a rotor, e.g. `"IV"`, `"VIII"` or `"Beta"`.
+    fn from_str(name: &str) -> Result<RotorWiring, String> {
+        match name.to_ascii_uppercase().as_str() {
+            "IC" => Ok(RotorWiring::Ic),
+            "IIC" => Ok(RotorWiring::Iic),
+            "IIIC" => Ok(RotorWiring::Iiic),
+            "I" => Ok(RotorWiring::I),
+            "II" => Ok(RotorWiring::Ii),
+            "III" => Ok(RotorWiring::Iii),
+            "IV" => Ok(RotorWiring::Iv),
+            "V" => Ok(RotorWiring::V),
+            "VI" => Ok(RotorWiring::Vi),
+            "VII" => Ok(RotorWiring::Vii),
+            "VIII" => Ok(RotorWiring::Viii),
+            "BETA" => Ok(RotorWiring::Beta),
+            "GAMMA" => Ok(RotorWiring::Gamma),
+            _ => Err(format!("unknown rotor {}", name)),
+        }
+    }
 }
 
-impl Into<Rotor> for RotorWiring {
-    fn into(self) -> Rotor {
-        match self {
-            Self::Ic => Rotor::new("DMTWSILRUYQNKFEJCAZBPGXOHV", "IC").unwrap(),
-            Self::Iic => Rotor::new("HQZGPJTMOBLNCIFDYAWVEUSRKX", "IIC").unwrap(),
-            Self::Iiic => Rotor::new("UQNTLSZFMREHDPXKIBVYGJCWOA", "IIIC").unwrap(),
-            Self::I => Rotor::new("EKMFLGDQVZNTOWYHXUSPAIBRCJ", "I").unwrap(),
-            Self::Ii => Rotor::new("AJDKSIRUXBLHWTMCQGZNPYFVOE", "II").unwrap(),
-            Self::Iii => Rotor::new("BDFHJLCPRTXVZNYEIWGAKMUSQO", "III").unwrap(),
+impl From<RotorWiring> for Rotor {
+    fn from(wiring: RotorWiring) -> Rotor {
+        match &wiring {
+            RotorWiring::Ic => Rotor::new("DMTWSILRUYQNKFEJCAZBPGXOHV", "IC"),
+            RotorWiring::Iic => Rotor::new("HQZGPJTMOBLNCIFDYAWVEUSRKX", "IIC"),
+            RotorWiring::Iiic => Rotor::new("UQNTLSZFMREHDPXKIBVYGJCWOA", "IIIC"),
+            RotorWiring::I => Rotor::new("EKMFLGDQVZNTOWYHXUSPAIBRCJ", "I"),
+            RotorWiring::Ii => Rotor::new("AJDKSIRUXBLHWTMCQGZNPYFVOE", "II"),
+            RotorWiring::Iii => Rotor::new("BDFHJLCPRTXVZNYEIWGAKMUSQO", "III"),
+            RotorWiring::Iv => Rotor::new("ESOVPZJAYQUIRHXLNFTGKDCMWB", "IV"),
+            RotorWiring::V => Rotor::new("VZBRGITYUPSDNHLXAWMJQOFECK", "V"),
+            RotorWiring::Vi => Rotor::new("JPGVOUMFYQBENHZRDKASXLICTW", "VI"),
+            RotorWiring::Vii => Rotor::new("NZJHGRCXMYSWBOUFAIVLPEKQDT", "VII"),
+            RotorWiring::Viii => Rotor::new("FKQHTLXOCBJSPDZRAMEWNIUYGV", "VIII"),
+            RotorWiring::Beta => Rotor::new("LEYJVCNIXWPBQMDRTAKZGFUHOS", "Beta"),
+            RotorWiring::Gamma => Rotor::new("FSOKANUERHMBTIYCWLQPZXVGJD", "Gamma"),
         }
+        .and_then(|rotor| {
+            rotor.with_notches(match wiring {
+                RotorWiring::I => "Q",
+                RotorWiring::Ii => "E",
+                RotorWiring::Iii => "V",
+                RotorWiring::Iv => "J",
+                RotorWiring::V => "Z",
+                RotorWiring::Vi | RotorWiring::Vii | RotorWiring::Viii => "ZM",
+                RotorWiring::Beta | RotorWiring::Gamma => "",
+                // turnover of the commercial rotors is not documented, they carry from Z to A
+                _ => "Z",
+            })
+        })
+        .unwrap()
     }
 }
 
+#[derive(Clone)]
 pub struct Rotor {
-    configuration: [char; 26],
+    alphabet: Alphabet,
+    wiring: Vec<usize>,
+    inverse: Vec<usize>,
     name: String,
     model_name: Option<String>,
     date_introduced: Option<String>,
-    rotations: u8,
-    carry_rotor: Option<Rc<RefCell<Rotor>>>,
+    rotations: usize,
+    ring_setting: usize,
+    notches: Vec<usize>,
 }
 
 impl Debug for Rotor {
@@ -67,72 +130,207 @@ impl Debug for Rotor {
 
 impl Rotor {
     pub fn new(wires: &str, name: &str) -> Option<Rotor> {
-        match wires
-            .to_ascii_lowercase()
-            .chars()
-            .collect::<Vec<char>>()
-            .try_into()
-        {
-            Ok(configuration) => Some(Rotor {
-                configuration,
-                name: name.to_string(),
-                model_name: None,
-                date_introduced: None,
-                rotations: 0,
-                carry_rotor: None,
-            }),
-            _ => None,
+        Rotor::with_alphabet(wires, name, Alphabet::latin())
+    }
+
+    /// a rotor whose wires connect the contacts of `alphabet` to the symbols in `wires`.
+    /// returns `None` unless `wires` holds every symbol of the alphabet exactly once.
+    pub fn with_alphabet(wires: &str, name: &str, alphabet: Alphabet) -> Option<Rotor> {
+        let wiring = alphabet.indices(wires)?;
+        Rotor::from_permutation(wiring, name, alphabet)
+    }
+
+    /// a rotor wiring contact `i` to contact `wiring[i]`.
+    pub fn from_permutation(wiring: Vec<usize>, name: &str, alphabet: Alphabet) -> Option<Rotor> {
+        if wiring.len() != alphabet.len() {
+            return None;
         }
+        let inverse = invert(&wiring)?;
+        Some(Rotor {
+            alphabet,
+            wiring,
+            inverse,
+            name: name.to_string(),
+            model_name: None,
+            date_introduced: None,
+            rotations: 0,
+            ring_setting: 0,
+            notches: vec![],
+        })
     }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// passes a symbol through `wiring` at the rotor's current position.
+    /// the ring setting turns the wiring against the letter ring, and with it the notches.
+    fn translate(&self, input: char, wiring: &[usize]) -> Option<char> {
+        let size = self.alphabet.len();
+        let offset = size + self.rotations - self.ring_setting;
+        let contact = (self.alphabet.index_of(input)? + offset) % size;
+        Some(self.alphabet.nth(wiring[contact] + size - offset % size))
+    }
+
     pub fn forward(&self, input: char) -> Option<char> {
-        let offset = 'a' as u8;
-        let limit = 'z' as u8;
-
-        let input = input.to_ascii_lowercase() as u8;
-        if input >= offset && input <= limit {
-            println!(
-                "translate {} in {} position. {}=>{}",
-                self.name,
-                n_abc(self.rotations as usize),
-                n_abc((input - offset) as usize),
-                self.configuration[((input - offset + self.rotations) % 26) as usize]
-                    .to_ascii_uppercase(),
-            );
-            Some(self.configuration[((input - offset + self.rotations) % 26) as usize])
-        } else {
-            None
-        }
+        self.translate(input, &self.wiring)
     }
 
     pub fn backward(&self, input: char) -> Option<char> {
-        let offset = 'a' as u8;
-        if let Some((index, _)) = self
-            .configuration
-            .iter()
-            .enumerate()
-            .find(|(_, c)| *c == &(input.to_ascii_lowercase()))
-        {
-            Some((index as u8 + self.rotations + offset) as char)
-        } else {
-            println!("could not find {} in {:?}", input, self.configuration);
-            None
-        }
+        self.translate(input, &self.inverse)
+    }
+
+    /// the letter currently shown in the rotor's window.
+    pub fn window(&self) -> String {
+        self.alphabet
+            .nth(self.rotations)
+            .to_ascii_uppercase()
+            .to_string()
+    }
+
+    /// sets the letters at which the rotor lets the pawl to its left engage, e.g. `"Q"` for rotor I.
+    /// returns `None` if one of them is not part of the rotor's alphabet.
+    pub fn with_notches(mut self, notches: &str) -> Option<Rotor> {
+        self.notches = self.alphabet.indices(notches)?;
+        Some(self)
+    }
+
+    /// the positions at which the rotor lets the pawl to its left engage.
+    pub fn notches(&self) -> &[usize] {
+        &self.notches
+    }
+
+    pub fn at_notch(&self) -> bool {
+        self.notches.contains(&self.rotations)
+    }
+
+    pub fn position(&self) -> usize {
+        self.rotations
+    }
+
+    pub fn set_position(&mut self, position: usize) {
+        self.rotations = position % self.alphabet.len();
+    }
+
+    pub fn ring_setting(&self) -> usize {
+        self.ring_setting
+    }
+
+    /// the Ringstellung, `0` for `A` (or `01`).
+    pub fn set_ring_setting(&mut self, ring_setting: usize) {
+        self.ring_setting = ring_setting % self.alphabet.len();
     }
 
     pub fn increment(&mut self) {
-        let prev = self.rotations;
-        self.rotations += 1;
-        println!("{} from {} to {}", self.name, prev, self.rotations);
-        if self.rotations % 26 != self.rotations {
-            self.rotations = self.rotations % 26;
-            if let Some(other) = &self.carry_rotor {
-                other.borrow_mut().increment();
-            } else {
-                // dropped increment
-            }
+        self.rotations = (self.rotations + 1) % self.alphabet.len();
+    }
+}
+
+/// steps a stack of moving rotors, fastest first, the way the pawls of an Enigma or Typex do.
+/// a pawl that drops into a notch moves both rotors it rests on,
+/// which makes a middle rotor standing at its notch move twice in a row (the double step).
+pub fn step_rotors(rotors: &mut [Rotor]) {
+    let steps = (0..rotors.len())
+        .map(|index| {
+            index == 0
+                || rotors[index - 1].at_notch()
+                || (index + 1 < rotors.len() && rotors[index].at_notch())
+        })
+        .collect::<Vec<bool>>();
+    rotors
+        .iter_mut()
+        .zip(steps)
+        .filter(|(_, steps)| *steps)
+        .for_each(|(rotor, _)| rotor.increment());
+}
+
+#[cfg(test)]
+mod test {
+    use super::{step_rotors, Rotor, RotorWiring};
+    use crate::algorythms::cryptography::enigma::utils::Alphabet;
+
+    #[test]
+    fn double_steps() {
+        // I-II-III at ADU, listed fastest first
+        let mut rotors = [RotorWiring::Iii, RotorWiring::Ii, RotorWiring::I]
+            .map(Rotor::from)
+            .to_vec();
+        rotors[0].set_position(20);
+        rotors[1].set_position(3);
+        let mut windows = vec![];
+        for _ in 0..4 {
+            step_rotors(&mut rotors);
+            windows.push(rotors.iter().rev().map(Rotor::window).collect::<String>());
         }
+        assert_eq!(windows, vec!["ADV", "AEW", "BFX", "BFY"]);
     }
-    pub fn set_carry_rotor(&mut self, rotor: Option<Rc<RefCell<Rotor>>>) {
-        self.carry_rotor = rotor.clone();
+
+    #[test]
+    fn multiple_notches() {
+        let mut rotors = vec![
+            Rotor::new("EKMFLGDQVZNTOWYHXUSPAIBRCJ", "fast")
+                .unwrap()
+                .with_notches("AN")
+                .unwrap(),
+            Rotor::new("AJDKSIRUXBLHWTMCQGZNPYFVOE", "slow").unwrap(),
+        ];
+        (0..26).for_each(|_| step_rotors(&mut rotors));
+        assert_eq!(rotors[1].position(), 2);
+        assert!(Rotor::new("EKMFLGDQVZNTOWYHXUSPAIBRCJ", "I")
+            .unwrap()
+            .with_notches("1")
+            .is_none());
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("viii".parse(), Ok(RotorWiring::Viii));
+        assert_eq!("Beta".parse(), Ok(RotorWiring::Beta));
+        assert!("IX".parse::<RotorWiring>().is_err());
+        assert!(RotorWiring::Gamma.is_greek());
+        assert!(!RotorWiring::V.is_greek());
+    }
+
+    #[test]
+    fn ring_setting() {
+        let mut rotor = Rotor::from(RotorWiring::I);
+        rotor.set_ring_setting(1);
+        // the wiring moves on by one: A enters at the contact of Z, which is wired to J
+        assert_eq!(rotor.forward('a'), Some('k'));
+        assert_eq!(rotor.backward('k'), Some('a'));
+        rotor.set_position(16);
+        // the notch stays on the letter ring
+        assert!(rotor.at_notch());
+    }
+
+    #[test]
+    fn rejects_invalid_wiring() {
+        assert!(Rotor::new("EKMFLGDQVZNTOWYHXUSPAIBRC", "too short").is_none());
+        assert!(Rotor::new("EKMFLGDQVZNTOWYHXUSPAIBRCC", "C twice").is_none());
+        assert!(
+            Rotor::from_permutation(vec![0, 2, 2], "2 twice", Alphabet::new("abc").unwrap())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn other_alphabets() {
+        let digits = Alphabet::new("0123456789").unwrap();
+        let mut rotor = Rotor::with_alphabet("3917460528", "digits", digits)
+            .unwrap()
+            .with_notches("9")
+            .unwrap();
+        assert_eq!(rotor.forward('0'), Some('3'));
+        assert_eq!(rotor.backward('3'), Some('0'));
+        rotor.set_position(9);
+        assert!(rotor.at_notch());
+        assert_eq!(rotor.window(), "9");
+        // contact 9 is wired to 8, which leaves one position back
+        assert_eq!(rotor.forward('0'), Some('9'));
+        assert_eq!(rotor.backward('9'), Some('0'));
     }
 }
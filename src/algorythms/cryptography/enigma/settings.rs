@@ -0,0 +1,346 @@
+use super::{Enigma, Plugboard, ReflectorWiring, RotorWiring, Uhr};
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// three rotors out of I to VIII and a wide reflector.
+    M3,
+    /// a greek wheel and three rotors out of I to VIII, with a thin reflector.
+    M4,
+}
+
+impl FromStr for Model {
+    type Err = SettingsError;
+
+    fn from_str(name: &str) -> Result<Model, SettingsError> {
+        match name.to_ascii_uppercase().as_str() {
+            "M3" => Ok(Model::M3),
+            "M4" => Ok(Model::M4),
+            _ => Err(SettingsError::UnknownModel(name.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsError {
+    UnknownModel(String),
+    UnknownRotor(String),
+    UnknownReflector(String),
+    /// the rotors do not fit the model, e.g. a greek wheel in an M3.
+    InvalidRotorOrder(Model, Vec<RotorWiring>),
+    InvalidReflector(Model, ReflectorWiring),
+    /// ring settings or positions that are not one letter (or number 1-26) per rotor.
+    InvalidDial(String),
+    InvalidPlugs(String),
+    InvalidUhr(usize),
+    /// a key sheet line that could not be read, with its line number.
+    InvalidKeySheetLine(usize, String),
+    DayNotFound(u32),
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::UnknownModel(name) => write!(f, "unknown model {}", name),
+            SettingsError::UnknownRotor(name) => write!(f, "unknown rotor {}", name),
+            SettingsError::UnknownReflector(name) => write!(f, "unknown reflector {}", name),
+            SettingsError::InvalidRotorOrder(model, rotors) => {
+                write!(f, "rotors {:?} do not fit the {:?}", rotors, model)
+            }
+            SettingsError::InvalidReflector(model, reflector) => {
+                write!(f, "reflector {:?} does not fit the {:?}", reflector, model)
+            }
+            SettingsError::InvalidDial(dial) => {
+                write!(f, "{} is not one letter or number per rotor", dial)
+            }
+            SettingsError::InvalidPlugs(plugs) => write!(f, "invalid plugs {}", plugs),
+            SettingsError::InvalidUhr(position) => {
+                write!(
+                    f,
+                    "the Uhr needs 10 plug pairs and a position below 40, got {}",
+                    position
+                )
+            }
+            SettingsError::InvalidKeySheetLine(line, reason) => {
+                write!(f, "key sheet line {}: {}", line, reason)
+            }
+            SettingsError::DayNotFound(day) => write!(f, "no key for day {}", day),
+        }
+    }
+}
+
+/// reads rotor names separated by commas or spaces, e.g. `"I,II,III"` or `"Beta II IV I"`.
+pub fn parse_rotors(rotors: &str) -> Result<Vec<RotorWiring>, SettingsError> {
+    rotors
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            name.parse()
+                .map_err(|_| SettingsError::UnknownRotor(name.to_string()))
+        })
+        .collect()
+}
+
+/// reads ring settings or positions, either as letters (`"BUL"`)
+/// or as numbers from 1 to 26 like on the key sheets (`"02 21 12"`), and returns them as letters.
+pub fn parse_dial(dial: &str) -> Result<String, SettingsError> {
+    let invalid = || SettingsError::InvalidDial(dial.to_string());
+    if dial.chars().any(|c| c.is_ascii_digit()) {
+        dial.split(|c: char| c == ',' || c == '-' || c.is_whitespace())
+            .filter(|number| !number.is_empty())
+            .map(|number| match number.parse::<u8>() {
+                Ok(number @ 1..=26) => Ok((b'A' + number - 1) as char),
+                _ => Err(invalid()),
+            })
+            .collect()
+    } else if dial.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(dial.to_ascii_uppercase())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// everything needed to set up a machine, with rotors, rings and positions read from left to right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnigmaSettings {
+    pub model: Model,
+    pub reflector: ReflectorWiring,
+    pub rotors: Vec<RotorWiring>,
+    pub rings: String,
+    pub positions: String,
+    pub plugs: String,
+    /// dial position of an [`Uhr`] taking the place of the plug cables.
+    pub uhr: Option<usize>,
+}
+
+impl EnigmaSettings {
+    /// settings with all rings and positions at `A` and no plugs.
+    /// the model follows from the number of rotors.
+    pub fn new(rotors: Vec<RotorWiring>, reflector: ReflectorWiring) -> EnigmaSettings {
+        EnigmaSettings {
+            model: if rotors.len() == 4 {
+                Model::M4
+            } else {
+                Model::M3
+            },
+            reflector,
+            rings: "A".repeat(rotors.len()),
+            positions: "A".repeat(rotors.len()),
+            rotors,
+            plugs: String::new(),
+            uhr: None,
+        }
+    }
+
+    pub fn build(&self) -> Result<Enigma, SettingsError> {
+        let (count, greek) = match self.model {
+            Model::M3 => (3, false),
+            Model::M4 => (4, true),
+        };
+        // a machine holds every rotor at most once
+        let distinct = self
+            .rotors
+            .iter()
+            .enumerate()
+            .all(|(index, rotor)| !self.rotors[..index].contains(rotor));
+        let fits_model = self.rotors.len() == count
+            && distinct
+            && self
+                .rotors
+                .iter()
+                .enumerate()
+                .all(|(index, rotor)| rotor.is_greek() == (greek && index == 0));
+        if !fits_model {
+            return Err(SettingsError::InvalidRotorOrder(
+                self.model,
+                self.rotors.clone(),
+            ));
+        }
+        if self.reflector.is_thin() != greek {
+            return Err(SettingsError::InvalidReflector(self.model, self.reflector));
+        }
+
+        let mut rotors = self.rotors.clone();
+        rotors.reverse();
+        let mut enigma = Enigma::new(rotors, self.reflector);
+        enigma
+            .set_ring_settings(&parse_dial(&self.rings)?)
+            .ok_or_else(|| SettingsError::InvalidDial(self.rings.clone()))?;
+        enigma
+            .set_positions(&parse_dial(&self.positions)?)
+            .ok_or_else(|| SettingsError::InvalidDial(self.positions.clone()))?;
+        let plugboard = match self.uhr {
+            Some(position) => Uhr::new(&self.plugs, position)
+                .map(|uhr| uhr.plugboard())
+                .ok_or(SettingsError::InvalidUhr(position))?,
+            None => Plugboard::new(&self.plugs)
+                .ok_or_else(|| SettingsError::InvalidPlugs(self.plugs.clone()))?,
+        };
+        Ok(enigma
+            .with_plugboard(plugboard)
+            .expect("the plugboard is made for the latin alphabet"))
+    }
+}
+
+/// a month of daily keys, one line per day with columns separated by `|`:
+///
+/// ```text
+/// # day | reflector | rotors     | rings    | plugs
+///   31  | B         | I IV III   | 16 26 08 | AD CN ET FL GI JV KZ PU QY WX
+/// ```
+///
+/// empty lines and lines starting with `#` are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySheet {
+    days: Vec<(u32, EnigmaSettings)>,
+}
+
+impl KeySheet {
+    pub fn day(&self, day: u32) -> Result<&EnigmaSettings, SettingsError> {
+        self.days
+            .iter()
+            .find(|(number, _)| *number == day)
+            .map(|(_, settings)| settings)
+            .ok_or(SettingsError::DayNotFound(day))
+    }
+
+    fn parse_line(line: &str) -> Result<(u32, EnigmaSettings), SettingsError> {
+        let columns = line.split('|').map(str::trim).collect::<Vec<&str>>();
+        let [day, reflector, rotors, rings, plugs] = columns.as_slice() else {
+            return Err(SettingsError::InvalidKeySheetLine(
+                0,
+                format!("expected 5 columns, found {}", columns.len()),
+            ));
+        };
+        let day = day
+            .parse()
+            .map_err(|_| SettingsError::InvalidKeySheetLine(0, format!("{} is not a day", day)))?;
+        let reflector = reflector
+            .parse()
+            .map_err(|_| SettingsError::UnknownReflector(reflector.to_string()))?;
+        let mut settings = EnigmaSettings::new(parse_rotors(rotors)?, reflector);
+        settings.rings = parse_dial(rings)?;
+        settings.plugs = plugs.to_string();
+        // surface mistakes on the sheet rather than when the day's key is used
+        settings.build()?;
+        Ok((day, settings))
+    }
+}
+
+impl FromStr for KeySheet {
+    type Err = SettingsError;
+
+    fn from_str(sheet: &str) -> Result<KeySheet, SettingsError> {
+        let days = sheet
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| {
+                KeySheet::parse_line(line).map_err(|err| match err {
+                    SettingsError::InvalidKeySheetLine(_, reason) => {
+                        SettingsError::InvalidKeySheetLine(index + 1, reason)
+                    }
+                    err => SettingsError::InvalidKeySheetLine(index + 1, err.to_string()),
+                })
+            })
+            .collect::<Result<Vec<(u32, EnigmaSettings)>, SettingsError>>()?;
+        Ok(KeySheet { days })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_dial, parse_rotors, EnigmaSettings, KeySheet, Model, SettingsError};
+    use crate::algorythms::cryptography::enigma::{ReflectorWiring, RotorWiring};
+
+    #[test]
+    fn operation_barbarossa() {
+        // first part of a message sent on 7 July 1941
+        let mut settings =
+            EnigmaSettings::new(parse_rotors("II IV V").unwrap(), ReflectorWiring::B);
+        settings.rings = "02 21 12".to_string();
+        settings.positions = "BLA".to_string();
+        settings.plugs = "AV BS CG DL FU HZ IN KM OW RX".to_string();
+        let mut enigma = settings.build().unwrap();
+        assert_eq!(
+            enigma.encode("EDPUD NRGYS ZRCXN UYTPO MRMBO FKTBZ REZKM LXLVE FGUEY SIOZV EQMIK UBPMM YLKLT TDEIS MDICA GYKUA CTCDO MOHWX MUUIA UBSTS LRNBZ SZWNR FXWFY SSXJZ VIJHI DISHP RKLKA YUPAD TXQSP INQMA TLPIF SVKDA SCTAC DPBOP VHJK").to_ascii_uppercase(),
+            "AUFKLXABTEILUNGXVONXKURTINOWAXKURTINOWAXNORDWESTLXSEBEZXSEBEZXUAFFLIEGERSTRASZERIQTUNGXDUBROWKIXDUBROWKIXOPOTSCHKAXOPOTSCHKAXUMXEINSAQTDREINULLXUHRANGETRETENXANGRIFFXINFXRGTX"
+        );
+    }
+
+    #[test]
+    fn validates_model() {
+        let m4 = EnigmaSettings::new(
+            parse_rotors("Beta,II,IV,I").unwrap(),
+            ReflectorWiring::Bthin,
+        );
+        assert_eq!(m4.model, Model::M4);
+        assert!(m4.build().is_ok());
+
+        let mut wide_reflector = m4.clone();
+        wide_reflector.reflector = ReflectorWiring::B;
+        assert_eq!(
+            wide_reflector.build().err(),
+            Some(SettingsError::InvalidReflector(
+                Model::M4,
+                ReflectorWiring::B
+            ))
+        );
+        let greek_in_m3 =
+            EnigmaSettings::new(parse_rotors("I,Beta,III").unwrap(), ReflectorWiring::B);
+        assert!(greek_in_m3.build().is_err());
+        let twice = EnigmaSettings::new(parse_rotors("I,I,III").unwrap(), ReflectorWiring::B);
+        assert!(twice.build().is_err());
+        let apart = EnigmaSettings::new(parse_rotors("I,II,I").unwrap(), ReflectorWiring::B);
+        assert!(apart.build().is_err());
+        assert_eq!(
+            parse_rotors("I,IX"),
+            Err(SettingsError::UnknownRotor("IX".to_string()))
+        );
+        assert_eq!("m4".parse(), Ok(Model::M4));
+    }
+
+    #[test]
+    fn dials() {
+        assert_eq!(parse_dial("bul"), Ok("BUL".to_string()));
+        assert_eq!(parse_dial("02 21 12"), Ok("BUL".to_string()));
+        assert_eq!(parse_dial("2,21,12"), Ok("BUL".to_string()));
+        assert!(parse_dial("02 27").is_err());
+        assert!(parse_dial("A1").is_err());
+
+        let mut settings =
+            EnigmaSettings::new(parse_rotors("I II III").unwrap(), ReflectorWiring::B);
+        settings.rings = "AB".to_string();
+        assert!(settings.build().is_err());
+    }
+
+    #[test]
+    fn key_sheet() {
+        let sheet: KeySheet = "
+            # day | reflector | rotors       | rings       | plugs
+              31  | B         | I IV III     | 16 26 08    | AD CN ET FL GI JV KZ PU QY WX
+              12  | C-thin    | Gamma V I II | 01 01 01 01 |
+        "
+        .parse()
+        .unwrap();
+        let day = sheet.day(31).unwrap();
+        assert_eq!(
+            day.rotors,
+            vec![RotorWiring::I, RotorWiring::Iv, RotorWiring::Iii]
+        );
+        assert_eq!(day.rings, "PZH");
+        assert_eq!(sheet.day(12).unwrap().model, Model::M4);
+        assert_eq!(sheet.day(1).err(), Some(SettingsError::DayNotFound(1)));
+
+        let broken = "31 | B | I IV III | 16 26 08\n".parse::<KeySheet>();
+        assert!(matches!(
+            broken,
+            Err(SettingsError::InvalidKeySheetLine(1, _))
+        ));
+        let broken = "\n31 | B | I IV IX | 16 26 08 | \n".parse::<KeySheet>();
+        assert!(matches!(
+            broken,
+            Err(SettingsError::InvalidKeySheetLine(2, _))
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+use super::Enigma;
+use std::io::{self, Read, Write};
+
+/// the stream adapters read every byte as the char with the same code point,
+/// so the machine's alphabet has to stay within `'\0'..='\u{ff}'` to be written back.
+fn to_byte(c: char) -> io::Result<u8> {
+    u8::try_from(c).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} does not fit into a byte", c),
+        )
+    })
+}
+
+/// iterator returned by [`Enigma::encrypt_iter`].
+pub struct EncryptIter<'a, I> {
+    enigma: &'a mut Enigma,
+    chars: I,
+}
+
+impl<'a, I> EncryptIter<'a, I> {
+    pub fn new(enigma: &'a mut Enigma, chars: I) -> Self {
+        EncryptIter { enigma, chars }
+    }
+}
+
+impl<I> Iterator for EncryptIter<'_, I>
+where
+    I: Iterator<Item = char>,
+{
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.by_ref().find_map(|c| self.enigma.encode_char(c))
+    }
+}
+
+/// encrypts everything written to it and passes the result on to `inner`.
+/// bytes the machine has no key for are dropped, just like [`Enigma::encode`] does.
+pub struct EnigmaWriter<W> {
+    enigma: Enigma,
+    inner: W,
+}
+
+impl<W: Write> EnigmaWriter<W> {
+    pub fn new(enigma: Enigma, inner: W) -> Self {
+        EnigmaWriter { enigma, inner }
+    }
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    pub fn into_parts(self) -> (Enigma, W) {
+        (self.enigma, self.inner)
+    }
+}
+
+impl<W: Write> Write for EnigmaWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let encoded = buf
+            .iter()
+            .filter_map(|byte| self.enigma.encode_char(*byte as char))
+            .map(to_byte)
+            .collect::<io::Result<Vec<u8>>>()?;
+        self.inner.write_all(&encoded)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// encrypts everything read from `inner`.
+/// bytes the machine has no key for are dropped, just like [`Enigma::encode`] does.
+pub struct EnigmaReader<R> {
+    enigma: Enigma,
+    inner: R,
+}
+
+impl<R: Read> EnigmaReader<R> {
+    pub fn new(enigma: Enigma, inner: R) -> Self {
+        EnigmaReader { enigma, inner }
+    }
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    pub fn into_parts(self) -> (Enigma, R) {
+        (self.enigma, self.inner)
+    }
+}
+
+impl<R: Read> Read for EnigmaReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.inner.read(buf)?;
+            if read == 0 {
+                return Ok(0);
+            }
+            let mut encoded = 0;
+            for index in 0..read {
+                if let Some(c) = self.enigma.encode_char(buf[index] as char) {
+                    buf[encoded] = to_byte(c)?;
+                    encoded += 1;
+                }
+            }
+            // a chunk made up of dropped bytes only must not look like the end of the stream
+            if encoded > 0 {
+                return Ok(encoded);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EnigmaReader, EnigmaWriter};
+    use crate::algorythms::cryptography::enigma::{
+        test::random_enigma, Alphabet, Enigma, ReflectorWiring, RotorWiring,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::io::{Read, Write};
+
+    fn enigma() -> Enigma {
+        Enigma::new(
+            vec![RotorWiring::Iii, RotorWiring::Ii, RotorWiring::I],
+            ReflectorWiring::B,
+        )
+    }
+
+    #[test]
+    fn writer_keeps_state_across_chunks() {
+        let plaintext = "attackatdawn".repeat(50);
+        let mut writer = EnigmaWriter::new(enigma(), vec![]);
+        for chunk in plaintext.as_bytes().chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+        let (_, ciphertext) = writer.into_parts();
+        assert_eq!(
+            String::from_utf8(ciphertext).unwrap(),
+            enigma().encode(&plaintext)
+        );
+    }
+
+    #[test]
+    fn reader_decrypts_writer_output() {
+        let plaintext = "Attack at dawn!\n".repeat(100);
+        let mut writer = EnigmaWriter::new(enigma(), vec![]);
+        writer.write_all(plaintext.as_bytes()).unwrap();
+
+        let (_, ciphertext) = writer.into_parts();
+        let mut reader = EnigmaReader::new(enigma(), ciphertext.as_slice());
+        let mut decrypted = String::new();
+        reader.read_to_string(&mut decrypted).unwrap();
+        assert_eq!(decrypted, "attackatdawn".repeat(100));
+    }
+
+    #[test]
+    fn reader_skips_chunks_without_letters() {
+        let mut reader = EnigmaReader::new(enigma(), "a ... a".as_bytes());
+        let mut buf = [0; 2];
+        assert_eq!(reader.read(&mut buf).unwrap(), 1);
+        assert_eq!(reader.read(&mut buf).unwrap(), 1);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn byte_round_trips() {
+        let mut rng = StdRng::seed_from_u64(26);
+        for _ in 0..50 {
+            let seed = rng.gen();
+            let rotors = rng.gen_range(1..=5);
+            let plaintext = (0..rng.gen_range(0..2000))
+                .map(|_| rng.gen())
+                .collect::<Vec<u8>>();
+
+            let mut writer =
+                EnigmaWriter::new(random_enigma(seed, Alphabet::bytes(), rotors), vec![]);
+            for chunk in plaintext.chunks(rng.gen_range(1..100)) {
+                writer.write_all(chunk).unwrap();
+            }
+            let (_, ciphertext) = writer.into_parts();
+            assert_eq!(ciphertext.len(), plaintext.len());
+
+            let mut decrypted = vec![];
+            EnigmaReader::new(
+                random_enigma(seed, Alphabet::bytes(), rotors),
+                ciphertext.as_slice(),
+            )
+            .read_to_end(&mut decrypted)
+            .unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+
+    #[test]
+    fn unwritable_alphabet() {
+        let alphabet = Alphabet::new("aж").unwrap();
+        let mut writer = EnigmaWriter::new(random_enigma(1, alphabet, 1), vec![]);
+        assert!(writer.write_all(b"a").is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use super::{plugboard::Plugboard, utils::Alphabet};
+
+pub const UHR_POSITIONS: usize = 40;
+pub const UHR_PAIRS: usize = 10;
+
+/// wiring of the Uhr's rotating disc from the published tables:
+/// contact `i` is connected to contact `DISC[i]`.
+const DISC: [usize; UHR_POSITIONS] = [
+    6, 31, 4, 29, 18, 39, 16, 25, 30, 23, 28, 1, 38, 11, 36, 37, 26, 27, 24, 21, 14, 3, 12, 17, 2,
+    7, 0, 33, 10, 35, 8, 5, 22, 19, 20, 13, 34, 15, 32, 9,
+];
+
+/// the Luftwaffe's Uhr box, which replaces the ten plugboard cables with plugs
+/// `1a`..`10a` and `1b`..`10b` leading into a 40 position switch.
+///
+/// plug `na` sits on the contacts `4(n-1)` (keyboard side) and `4(n-1)+1` (entry wheel side),
+/// plug `nb` on `4(n-1)+3` (keyboard side) and `4(n-1)+2` (entry wheel side).
+/// the disc always connects a keyboard side pin with an entry wheel side one,
+/// but not with the partner plug, so the resulting wiring is in general not reciprocal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uhr {
+    pairs: Vec<(usize, usize)>,
+    position: usize,
+}
+
+impl Uhr {
+    /// takes the ten cable pairs of the key sheet, e.g. `"AB CD ..."`,
+    /// where the first letter of a pair gets the `a` plug and the second one the `b` plug.
+    pub fn new(pairs: &str, position: usize) -> Option<Uhr> {
+        let pairs = Plugboard::parse_pairs(pairs, &Alphabet::latin())?;
+        if pairs.len() == UHR_PAIRS && position < UHR_POSITIONS {
+            Some(Uhr { pairs, position })
+        } else {
+            None
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: usize) {
+        self.position = position % UHR_POSITIONS;
+    }
+
+    /// the letter plugged into whatever plug owns `contact`.
+    fn letter(&self, contact: usize) -> usize {
+        let (a, b) = self.pairs[contact / 4];
+        match contact % 4 {
+            0 | 1 => a,
+            _ => b,
+        }
+    }
+
+    /// the wiring from the keyboard to the entry wheel at the current dial position.
+    pub fn plugboard(&self) -> Plugboard {
+        let mut wiring = (0..26).collect::<Vec<usize>>();
+        for (plug, (a, b)) in self.pairs.iter().enumerate() {
+            for (letter, contact) in [(a, plug * 4), (b, plug * 4 + 3)] {
+                let wired = (DISC[(contact + self.position) % UHR_POSITIONS] + UHR_POSITIONS
+                    - self.position)
+                    % UHR_POSITIONS;
+                wiring[*letter] = self.letter(wired);
+            }
+        }
+        Plugboard::from_permutation(wiring, Alphabet::latin())
+            .expect("the disc connects every keyboard pin with a different entry wheel pin")
+    }
+}
+
+impl From<&Uhr> for Plugboard {
+    fn from(uhr: &Uhr) -> Plugboard {
+        uhr.plugboard()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Uhr, UHR_POSITIONS};
+
+    const PAIRS: &str = "AB CD EF GH IJ KL MN OP QR ST";
+
+    #[test]
+    fn only_plugged_letters_move() {
+        for position in 0..UHR_POSITIONS {
+            let plugboard = Uhr::new(PAIRS, position).unwrap().plugboard();
+            for letter in "uvwxyz".chars() {
+                assert_eq!(plugboard.forward(letter), Some(letter));
+            }
+            for letter in "abcdefghijklmnopqrst".chars() {
+                assert!("abcdefghijklmnopqrst".contains(plugboard.forward(letter).unwrap()));
+                assert_eq!(
+                    plugboard.backward(plugboard.forward(letter).unwrap()),
+                    Some(letter)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dial_mapping() {
+        // worked out by hand from the published disc wiring. at position 1, plug 1a (A) sits on
+        // contact 0, which the turned disc leads to contact 31 - 1 = 30, the entry wheel side of
+        // plug 8b (P)
+        for (position, letters) in [
+            (0, "DOJMPATSNKHIBQFCLGRE"),
+            (1, "PITOLSFMNGBADERKJQHC"),
+            (4, "HKNSRQLIFGTODAJEPCBM"),
+            (27, "DKJQNCLIBOTAHEFSRMPG"),
+        ] {
+            let plugboard = Uhr::new(PAIRS, position).unwrap().plugboard();
+            let wired: String = "abcdefghijklmnopqrst"
+                .chars()
+                .map(|letter| plugboard.forward(letter).unwrap())
+                .collect();
+            assert_eq!(wired, letters.to_lowercase());
+        }
+    }
+
+    #[test]
+    fn is_not_reciprocal() {
+        let non_reciprocal = (0..UHR_POSITIONS)
+            .filter(|position| {
+                !Uhr::new(PAIRS, *position)
+                    .unwrap()
+                    .plugboard()
+                    .is_reciprocal()
+            })
+            .count();
+        assert!(non_reciprocal > 0);
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(Uhr::new("AB CD", 0).is_none());
+        assert!(Uhr::new(PAIRS, 40).is_none());
+        assert!(Uhr::new("AB CD EF GH IJ KL MN OP QR SA", 0).is_none());
+        let mut uhr = Uhr::new(PAIRS, 39).unwrap();
+        uhr.set_position(41);
+        assert_eq!(uhr.position(), 1);
+    }
+}
@@ -1,25 +1,115 @@
-pub fn abc() -> String {
-    "abcdefghijklmnopqrstuvwxyz"
-        .to_string()
-        .to_ascii_uppercase()
+use std::collections::HashMap;
+
+/// the symbols a rotor machine has keys and lamps for.
+/// wirings are stored as permutations of the indices into an alphabet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+    indices: HashMap<char, usize>,
 }
 
-pub fn n_abc(index: usize) -> String {
-    let abc = abc();
-    abc.get(index % 26..=index % 26)
-        .unwrap()
-        .clone()
-        .to_string()
+impl Alphabet {
+    /// returns `None` for an empty alphabet or one that contains a symbol twice.
+    pub fn new(letters: &str) -> Option<Alphabet> {
+        let letters = letters.chars().collect::<Vec<char>>();
+        let indices = letters
+            .iter()
+            .enumerate()
+            .map(|(index, letter)| (*letter, index))
+            .collect::<HashMap<char, usize>>();
+        if letters.is_empty() || indices.len() != letters.len() {
+            None
+        } else {
+            Some(Alphabet { letters, indices })
+        }
+    }
+
+    /// the 26 letters of the Enigma keyboard.
+    pub fn latin() -> Alphabet {
+        Alphabet::new("abcdefghijklmnopqrstuvwxyz").unwrap()
+    }
+
+    /// latin letters followed by the digits.
+    pub fn alphanumeric() -> Alphabet {
+        Alphabet::new("abcdefghijklmnopqrstuvwxyz0123456789").unwrap()
+    }
+
+    /// all 256 byte values, each as the char with the same code point.
+    pub fn bytes() -> Alphabet {
+        Alphabet::new(&(0..=u8::MAX).map(char::from).collect::<String>()).unwrap()
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// finds a symbol, ignoring its case if the alphabet only has one of them.
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        [c, c.to_ascii_lowercase(), c.to_ascii_uppercase()]
+            .iter()
+            .find_map(|c| self.indices.get(c))
+            .copied()
+    }
+
+    /// the symbol at `index`, wrapping around at the end of the alphabet.
+    pub fn nth(&self, index: usize) -> char {
+        self.letters[index % self.letters.len()]
+    }
+
+    /// turns a string of symbols into their indices.
+    pub fn indices(&self, letters: &str) -> Option<Vec<usize>> {
+        letters.chars().map(|c| self.index_of(c)).collect()
+    }
+}
+
+/// inverts a wiring given as a permutation of `0..wiring.len()`.
+/// returns `None` if it is not a permutation, i.e. two contacts are wired to the same one.
+pub fn invert(wiring: &[usize]) -> Option<Vec<usize>> {
+    let mut inverse = vec![None; wiring.len()];
+    for (contact, wired) in wiring.iter().enumerate() {
+        match inverse.get_mut(*wired) {
+            Some(slot @ None) => *slot = Some(contact),
+            _ => return None,
+        }
+    }
+    inverse.into_iter().collect()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::algorythms::cryptography::enigma::utils::n_abc;
+    use super::{invert, Alphabet};
 
     #[test]
     fn nabc() {
-        assert_eq!(n_abc(0), "A".to_string());
-        assert_eq!(n_abc(26), "A".to_string());
-        assert_eq!(n_abc(25), "Z".to_string());
+        let latin = Alphabet::latin();
+        assert_eq!(latin.nth(0), 'a');
+        assert_eq!(latin.nth(26), 'a');
+        assert_eq!(latin.nth(25), 'z');
+    }
+
+    #[test]
+    fn lookup() {
+        let latin = Alphabet::latin();
+        assert_eq!(latin.index_of('C'), Some(2));
+        assert_eq!(latin.index_of('1'), None);
+        assert_eq!(Alphabet::alphanumeric().index_of('1'), Some(27));
+        let bytes = Alphabet::bytes();
+        assert_eq!(bytes.len(), 256);
+        assert_eq!(bytes.index_of('A'), Some(65));
+        assert_eq!(bytes.index_of('a'), Some(97));
+        assert_eq!(bytes.nth(255), '\u{ff}');
+        assert!(Alphabet::new("abca").is_none());
+        assert!(Alphabet::new("").is_none());
+    }
+
+    #[test]
+    fn inverts() {
+        assert_eq!(invert(&[2, 0, 1]), Some(vec![1, 2, 0]));
+        assert_eq!(invert(&[0, 0, 1]), None);
+        assert_eq!(invert(&[0, 3, 1]), None);
     }
 }
@@ -0,0 +1,208 @@
+pub mod analysis;
+pub mod baudot;
+mod wheel;
+pub use wheel::Pinwheel;
+
+/// pin counts of the five chi wheels, which move with every character.
+pub const CHI_SIZES: [usize; 5] = [41, 31, 29, 26, 23];
+/// pin counts of the five psi wheels, which move together whenever the motor wheels allow it.
+pub const PSI_SIZES: [usize; 5] = [43, 47, 51, 53, 59];
+/// pin counts of the motor wheels mu61 and mu37.
+pub const MU_SIZES: [usize; 2] = [61, 37];
+
+/// the Lorenz SZ40/42 ("Tunny") teleprinter cipher attachment.
+/// every character is xored with the chi and psi wheels, which makes the machine its own inverse.
+/// wheel positions count from 0.
+#[derive(Debug, Clone)]
+pub struct Lorenz {
+    chi: [Pinwheel; 5],
+    psi: [Pinwheel; 5],
+    mu: [Pinwheel; 2],
+}
+
+impl Lorenz {
+    /// returns `None` unless every wheel has the pin count of its historical counterpart.
+    pub fn new(chi: [Pinwheel; 5], psi: [Pinwheel; 5], mu: [Pinwheel; 2]) -> Option<Lorenz> {
+        let sizes_match = |wheels: &[Pinwheel], sizes: &[usize]| {
+            wheels
+                .iter()
+                .zip(sizes)
+                .all(|(wheel, size)| wheel.len() == *size)
+        };
+        if sizes_match(&chi, &CHI_SIZES)
+            && sizes_match(&psi, &PSI_SIZES)
+            && sizes_match(&mu, &MU_SIZES)
+        {
+            Some(Lorenz { chi, psi, mu })
+        } else {
+            None
+        }
+    }
+
+    /// builds a machine from pin patterns in Bletchley Park notation (see [`Pinwheel::from_pattern`]).
+    pub fn from_patterns(chi: [&str; 5], psi: [&str; 5], mu: [&str; 2]) -> Option<Lorenz> {
+        fn wheels<const N: usize>(patterns: [&str; N]) -> Option<[Pinwheel; N]> {
+            patterns
+                .iter()
+                .map(|pattern| Pinwheel::from_pattern(pattern))
+                .collect::<Option<Vec<Pinwheel>>>()?
+                .try_into()
+                .ok()
+        }
+        Lorenz::new(wheels(chi)?, wheels(psi)?, wheels(mu)?)
+    }
+
+    pub fn chi(&self) -> &[Pinwheel; 5] {
+        &self.chi
+    }
+    pub fn psi(&self) -> &[Pinwheel; 5] {
+        &self.psi
+    }
+    pub fn mu(&self) -> &[Pinwheel; 2] {
+        &self.mu
+    }
+
+    pub fn set_positions(&mut self, chi: [usize; 5], psi: [usize; 5], mu: [usize; 2]) {
+        self.chi
+            .iter_mut()
+            .zip(chi)
+            .chain(self.psi.iter_mut().zip(psi))
+            .chain(self.mu.iter_mut().zip(mu))
+            .for_each(|(wheel, position)| wheel.set_position(position));
+    }
+
+    pub fn positions(&self) -> ([usize; 5], [usize; 5], [usize; 2]) {
+        (
+            self.chi.clone().map(|wheel| wheel.position()),
+            self.psi.clone().map(|wheel| wheel.position()),
+            self.mu.clone().map(|wheel| wheel.position()),
+        )
+    }
+
+    fn code(wheels: &[Pinwheel; 5]) -> u8 {
+        wheels
+            .iter()
+            .fold(0, |code, wheel| (code << 1) | wheel.current() as u8)
+    }
+
+    /// the key character for the current position, chi xor psi.
+    pub fn key(&self) -> u8 {
+        Lorenz::code(&self.chi) ^ Lorenz::code(&self.psi)
+    }
+
+    /// moves the wheels on after a character.
+    /// chi wheels and mu61 always move, mu37 moves if mu61 showed a raised pin
+    /// and the psi wheels move if mu37 did.
+    pub fn increment(&mut self) {
+        let basic_motor = self.mu[0].current();
+        let total_motor = self.mu[1].current();
+        self.chi.iter_mut().for_each(Pinwheel::increment);
+        self.mu[0].increment();
+        if basic_motor {
+            self.mu[1].increment();
+        }
+        if total_motor {
+            self.psi.iter_mut().for_each(Pinwheel::increment);
+        }
+    }
+
+    pub fn encode_code(&mut self, code: u8) -> u8 {
+        let encoded = (code & 0b11111) ^ self.key();
+        self.increment();
+        encoded
+    }
+
+    pub fn encode_codes(&mut self, codes: &[u8]) -> Vec<u8> {
+        codes.iter().map(|code| self.encode_code(*code)).collect()
+    }
+
+    /// encodes text written in Bletchley Park notation (see [`baudot::to_bletchley`]),
+    /// characters outside of it are dropped.
+    pub fn encode(&mut self, input: &str) -> String {
+        input
+            .chars()
+            .filter_map(baudot::from_bletchley)
+            .map(|code| baudot::to_bletchley(self.encode_code(code)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::{baudot, Lorenz, Pinwheel, CHI_SIZES, MU_SIZES, PSI_SIZES};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random_wheels<const N: usize>(rng: &mut StdRng, sizes: [usize; N]) -> [Pinwheel; N] {
+        sizes.map(|size| Pinwheel::new((0..size).map(|_| rng.gen_bool(0.5)).collect()).unwrap())
+    }
+
+    pub fn random_lorenz(seed: u64) -> Lorenz {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Lorenz::new(
+            random_wheels(&mut rng, CHI_SIZES),
+            random_wheels(&mut rng, PSI_SIZES),
+            random_wheels(&mut rng, MU_SIZES),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rejects_wrong_wheel_sizes() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(Lorenz::new(
+            random_wheels(&mut rng, [41, 31, 29, 26, 24]),
+            random_wheels(&mut rng, PSI_SIZES),
+            random_wheels(&mut rng, MU_SIZES),
+        )
+        .is_none());
+        assert!(Lorenz::from_patterns(["x."; 5], ["x."; 5], ["x."; 2]).is_none());
+    }
+
+    #[test]
+    fn decodes_own_output() {
+        let plaintext = baudot::encode("Attack at dawn, 0500 hours.\r\n".repeat(20).as_str());
+        let mut sender = random_lorenz(7);
+        sender.set_positions([1, 2, 3, 4, 5], [6, 7, 8, 9, 10], [11, 12]);
+        let mut receiver = sender.clone();
+        let ciphertext = sender.encode_codes(&plaintext);
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(receiver.encode_codes(&ciphertext), plaintext);
+
+        let mut receiver = random_lorenz(7);
+        receiver.set_positions([1, 2, 3, 4, 5], [6, 7, 8, 9, 10], [11, 12]);
+        let ciphertext = ciphertext
+            .iter()
+            .map(|code| baudot::to_bletchley(*code))
+            .collect::<String>();
+        assert_eq!(
+            baudot::decode(
+                &receiver
+                    .encode(&ciphertext)
+                    .chars()
+                    .filter_map(baudot::from_bletchley)
+                    .collect::<Vec<u8>>()
+            ),
+            "ATTACK AT DAWN, 0500 HOURS.\r\n".repeat(20)
+        );
+    }
+
+    #[test]
+    fn motor_wheels_hold_the_psi_wheels() {
+        let mut lorenz = random_lorenz(3);
+        lorenz.mu = [
+            Pinwheel::from_pattern(&"x.".repeat(31)[1..]).unwrap(),
+            Pinwheel::from_pattern(&".".repeat(37)).unwrap(),
+        ];
+        lorenz.encode_codes(&[0; 100]);
+        let (chi, psi, mu) = lorenz.positions();
+        assert_eq!(chi, [100 % 41, 100 % 31, 100 % 29, 100 % 26, 100 % 23]);
+        assert_eq!(psi, [0; 5]);
+        // mu61 has its pins raised on odd positions only
+        assert_eq!(mu, [100 % 61, 49 % 37]);
+
+        lorenz.mu[1] = Pinwheel::from_pattern(&"x".repeat(37)).unwrap();
+        lorenz.set_positions([0; 5], [0; 5], [0; 2]);
+        lorenz.encode_codes(&[0; 100]);
+        assert_eq!(lorenz.positions().1, PSI_SIZES.map(|size| 100 % size));
+    }
+}
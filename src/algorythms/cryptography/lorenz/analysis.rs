@@ -0,0 +1,106 @@
+//! cryptanalysis helpers in the spirit of Colossus.
+//! the attack relies on the "delta" (xor of consecutive characters) of the psi stream
+//! being dot whenever the motor holds the psi wheels, so that the delta of the
+//! ciphertext leaks the delta of the chi stream.
+
+use super::{baudot::impulse, Pinwheel};
+
+/// xors every character with the one following it, so the result is one shorter.
+pub fn delta(stream: &[u8]) -> Vec<u8> {
+    stream
+        .windows(2)
+        .map(|pair| (pair[0] ^ pair[1]) & 0b11111)
+        .collect()
+}
+
+/// delta of a chi wheel's output, starting `start` pins in and running for `length` characters.
+pub fn delta_wheel(wheel: &Pinwheel, start: usize, length: usize) -> Vec<bool> {
+    (0..length)
+        .map(|index| {
+            wheel.pins()[(start + index) % wheel.len()]
+                ^ wheel.pins()[(start + index + 1) % wheel.len()]
+        })
+        .collect()
+}
+
+/// counts the characters for which the xor of the given impulses of the delta'd ciphertext
+/// and the delta'd chi wheels is a dot. each wheel is given as `(impulse, wheel, start)`,
+/// so `[(1, chi1, s1), (2, chi2, s2)]` is the famous 1+2 count.
+/// for the correct starts the count is noticeably higher than half the delta'd length.
+pub fn delta_chi_count(ciphertext: &[u8], wheels: &[(usize, &Pinwheel, usize)]) -> usize {
+    let delta_z = delta(ciphertext);
+    let delta_chi = wheels
+        .iter()
+        .map(|(_, wheel, start)| delta_wheel(wheel, *start, delta_z.len()))
+        .collect::<Vec<Vec<bool>>>();
+    delta_z
+        .iter()
+        .enumerate()
+        .filter(|(index, code)| {
+            let cross = wheels
+                .iter()
+                .zip(&delta_chi)
+                .fold(false, |acc, ((number, _, _), chi)| {
+                    acc ^ impulse(**code, *number) ^ chi[*index]
+                });
+            !cross
+        })
+        .count()
+}
+
+/// runs [`delta_chi_count`] for every pair of starts of two chi wheels,
+/// returning `((start_a, start_b), count)` with the highest counts first.
+pub fn rank_chi_settings(
+    ciphertext: &[u8],
+    (impulse_a, wheel_a): (usize, &Pinwheel),
+    (impulse_b, wheel_b): (usize, &Pinwheel),
+) -> Vec<((usize, usize), usize)> {
+    let mut scores = (0..wheel_a.len())
+        .flat_map(|start_a| (0..wheel_b.len()).map(move |start_b| (start_a, start_b)))
+        .map(|(start_a, start_b)| {
+            (
+                (start_a, start_b),
+                delta_chi_count(
+                    ciphertext,
+                    &[(impulse_a, wheel_a, start_a), (impulse_b, wheel_b, start_b)],
+                ),
+            )
+        })
+        .collect::<Vec<((usize, usize), usize)>>();
+    scores.sort_by(|(a, count_a), (b, count_b)| count_b.cmp(count_a).then(a.cmp(b)));
+    scores
+}
+
+#[cfg(test)]
+mod test {
+    use super::{delta, delta_chi_count, rank_chi_settings};
+    use crate::algorythms::cryptography::lorenz::{baudot, test::random_lorenz};
+
+    #[test]
+    fn delta_of_stream() {
+        assert_eq!(delta(&[0b11000, 0b10011, 0b10011]), vec![0b01011, 0]);
+        assert!(delta(&[1]).is_empty());
+    }
+
+    #[test]
+    fn finds_chi_1_and_2() {
+        // operators doubled spaces and shifts, which is what gives delta'd plaintext its bias
+        let plaintext = baudot::encode(
+            &"FURTHER  TO  MY  TELEGRAM  OF  THE  3RD,,  THE  SUPPLY  COLUMN  WILL  ARRIVE  \
+              AT  THE  BRIDGE  NEAR  THE  EASTERN  RAILWAY  STATION  AT  DAWN..  "
+                .repeat(20),
+        );
+        let mut lorenz = random_lorenz(42);
+        lorenz.set_positions([17, 5, 0, 0, 0], [3, 1, 4, 1, 5], [9, 2]);
+        let ciphertext = lorenz.encode_codes(&plaintext);
+
+        let chi = lorenz.chi();
+        let ranked = rank_chi_settings(&ciphertext, (1, &chi[0]), (2, &chi[1]));
+        assert_eq!(ranked[0].0, (17, 5));
+        assert_eq!(
+            ranked[0].1,
+            delta_chi_count(&ciphertext, &[(1, &chi[0], 17), (2, &chi[1], 5)])
+        );
+        assert!(ranked[0].1 > ranked[1].1);
+    }
+}
@@ -0,0 +1,140 @@
+//! ITA2 (Baudot-Murray) teleprinter code.
+//! codes are 5 bit values with impulse 1 as the most significant bit, so `T` (`....x`) is `1`.
+
+pub const NULL: u8 = 0b00000;
+pub const CARRIAGE_RETURN: u8 = 0b00010;
+pub const SPACE: u8 = 0b00100;
+pub const LINE_FEED: u8 = 0b01000;
+pub const FIGURES: u8 = 0b11011;
+pub const LETTERS: u8 = 0b11111;
+
+/// Bletchley Park's notation for all 32 codes, indexed by code.
+const BLETCHLEY: &str = "/T3O9HNM4LRGIPCVEZDBSYFXAWJ5UQK8";
+/// figures shift of the letters in `BLETCHLEY`, `_` where ITA2 leaves the code unassigned.
+const FIGURE_SHIFT: &str = "_5_9_#,._)4&80:=3+$?'6!/-2\u{7}_71(_";
+
+/// returns impulse `n` (1..=5) of a code.
+pub fn impulse(code: u8, n: usize) -> bool {
+    (code >> (5 - n)) & 1 == 1
+}
+
+/// the Bletchley Park character for a code, e.g. `9` for space.
+pub fn to_bletchley(code: u8) -> char {
+    BLETCHLEY.as_bytes()[(code & 0b11111) as usize] as char
+}
+
+pub fn from_bletchley(c: char) -> Option<u8> {
+    let c = match c {
+        '+' => '5',
+        '-' => '8',
+        c => c.to_ascii_uppercase(),
+    };
+    BLETCHLEY
+        .chars()
+        .position(|b| b == c)
+        .map(|code| code as u8)
+}
+
+fn letter(c: char) -> Option<u8> {
+    match c {
+        'A'..='Z' => from_bletchley(c),
+        _ => None,
+    }
+}
+
+fn figure(c: char) -> Option<u8> {
+    match c {
+        '_' => None,
+        c => FIGURE_SHIFT
+            .chars()
+            .position(|f| f == c)
+            .map(|code| code as u8),
+    }
+}
+
+/// encodes text into ITA2, inserting shift codes where needed.
+/// the stream starts in letters shift; characters without a code are dropped.
+pub fn encode(text: &str) -> Vec<u8> {
+    let mut figures = false;
+    let mut codes = vec![];
+    for c in text.chars().map(|c| c.to_ascii_uppercase()) {
+        match c {
+            ' ' => codes.push(SPACE),
+            '\r' => codes.push(CARRIAGE_RETURN),
+            '\n' => codes.push(LINE_FEED),
+            c => match (letter(c), figure(c)) {
+                (Some(code), _) => {
+                    if figures {
+                        codes.push(LETTERS);
+                        figures = false;
+                    }
+                    codes.push(code)
+                }
+                (None, Some(code)) => {
+                    if !figures {
+                        codes.push(FIGURES);
+                        figures = true;
+                    }
+                    codes.push(code)
+                }
+                _ => {}
+            },
+        }
+    }
+    codes
+}
+
+/// decodes ITA2 into text, following the shift codes.
+pub fn decode(codes: &[u8]) -> String {
+    let mut figures = false;
+    let mut text = String::new();
+    for code in codes.iter().map(|code| code & 0b11111) {
+        match code {
+            NULL => {}
+            SPACE => text.push(' '),
+            CARRIAGE_RETURN => text.push('\r'),
+            LINE_FEED => text.push('\n'),
+            FIGURES => figures = true,
+            LETTERS => figures = false,
+            code if figures => {
+                if let Some(c) = FIGURE_SHIFT
+                    .chars()
+                    .nth(code as usize)
+                    .filter(|c| *c != '_')
+                {
+                    text.push(c)
+                }
+            }
+            code => text.push(to_bletchley(code)),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, encode, from_bletchley, impulse, to_bletchley, FIGURES, LETTERS};
+
+    #[test]
+    fn letter_codes() {
+        // A is x x . . .
+        assert_eq!(from_bletchley('A'), Some(0b11000));
+        assert!(impulse(0b11000, 1));
+        assert!(impulse(0b11000, 2));
+        assert!(!impulse(0b11000, 3));
+        assert_eq!(to_bletchley(0b00100), '9');
+        assert_eq!(from_bletchley('+'), Some(FIGURES));
+        assert_eq!(from_bletchley('-'), Some(LETTERS));
+        for code in 0..32 {
+            assert_eq!(from_bletchley(to_bletchley(code)), Some(code));
+        }
+    }
+
+    #[test]
+    fn shifts() {
+        let codes = encode("Meet at 1500, pier 4");
+        assert_eq!(codes.iter().filter(|code| **code == FIGURES).count(), 2);
+        assert_eq!(codes.iter().filter(|code| **code == LETTERS).count(), 1);
+        assert_eq!(decode(&codes), "MEET AT 1500, PIER 4");
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::Debug;
+
+/// a Lorenz pinwheel. like a [`Rotor`](crate::algorythms::cryptography::enigma::Rotor)
+/// it only ever moves forward by one, but instead of wiring it carries a ring of pins
+/// that are either raised (`x`, 1) or lowered (`.`, 0).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Pinwheel {
+    pins: Vec<bool>,
+    rotations: usize,
+}
+
+impl Debug for Pinwheel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Pinwheel <{}, offset={}>",
+            self.pattern(),
+            self.rotations
+        )
+    }
+}
+
+impl Pinwheel {
+    pub fn new(pins: Vec<bool>) -> Option<Pinwheel> {
+        if pins.is_empty() {
+            None
+        } else {
+            Some(Pinwheel { pins, rotations: 0 })
+        }
+    }
+
+    /// reads a pin pattern in Bletchley Park notation, `x` for raised and `.` for lowered pins.
+    /// `1` and `0` are accepted as well, whitespace is ignored.
+    pub fn from_pattern(pattern: &str) -> Option<Pinwheel> {
+        pattern
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c {
+                'x' | 'X' | '1' => Some(true),
+                '.' | '0' => Some(false),
+                _ => None,
+            })
+            .collect::<Option<Vec<bool>>>()
+            .and_then(Pinwheel::new)
+    }
+
+    pub fn pattern(&self) -> String {
+        self.pins
+            .iter()
+            .map(|pin| if *pin { 'x' } else { '.' })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    pub fn pins(&self) -> &[bool] {
+        &self.pins
+    }
+
+    /// the pin `offset` places ahead of the one currently read.
+    pub fn pin(&self, offset: usize) -> bool {
+        self.pins[(self.rotations + offset) % self.pins.len()]
+    }
+
+    pub fn current(&self) -> bool {
+        self.pin(0)
+    }
+
+    pub fn position(&self) -> usize {
+        self.rotations
+    }
+
+    pub fn set_position(&mut self, position: usize) {
+        self.rotations = position % self.pins.len();
+    }
+
+    pub fn increment(&mut self) {
+        self.rotations = (self.rotations + 1) % self.pins.len();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Pinwheel;
+
+    #[test]
+    fn steps_around() {
+        let mut wheel = Pinwheel::from_pattern("x.. x.").unwrap();
+        assert_eq!(wheel.len(), 5);
+        assert_eq!(wheel.pattern(), "x..x.");
+        let read = (0..7)
+            .map(|_| {
+                let pin = wheel.current();
+                wheel.increment();
+                pin
+            })
+            .collect::<Vec<bool>>();
+        assert_eq!(read, vec![true, false, false, true, false, true, false]);
+        assert_eq!(wheel.position(), 2);
+        assert!(Pinwheel::from_pattern("x.y").is_none());
+        assert!(Pinwheel::from_pattern("").is_none());
+    }
+}
@@ -0,0 +1,182 @@
+use super::lorenz::Pinwheel;
+
+/// letters printed on the six pin wheels, wheel 1 (the leftmost) first.
+pub const WHEEL_LETTERS: [&str; 6] = [
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    "ABCDEFGHIJKLMNOPQRSTUVXYZ",
+    "ABCDEFGHIJKLMNOPQRSTUVX",
+    "ABCDEFGHIJKLMNOPQRSTU",
+    "ABCDEFGHIJKLMNOPQRS",
+    "ABCDEFGHIJKLMNOPQ",
+];
+/// the pin sensed by the guide arm sits this far ahead of the letter shown in the window.
+const ACTIVE_OFFSET: [usize; 6] = [15, 14, 13, 12, 11, 10];
+pub const BARS: usize = 27;
+
+/// the Hagelin M-209 (C-38) pin-wheel machine.
+/// every raised pin under the guide arms kicks the cage bars carrying a lug for its wheel,
+/// the number of kicked bars shifts a reversed alphabet, so the machine is its own inverse.
+#[derive(Debug, Clone)]
+pub struct M209 {
+    wheels: [Pinwheel; 6],
+    lugs: Vec<(usize, usize)>,
+}
+
+impl M209 {
+    /// `pins` lists the letters of the raised pins of every wheel, e.g. `"ABDHIKMNSTVW"`.
+    /// `lugs` holds the two lug positions of each of the 27 bars, `1..=6` for a wheel and `0` for neutral.
+    pub fn new(pins: [&str; 6], lugs: &[(usize, usize)]) -> Option<M209> {
+        let valid_lug = |lug: &usize| *lug <= 6;
+        if lugs.len() != BARS || !lugs.iter().all(|(a, b)| valid_lug(a) && valid_lug(b)) {
+            return None;
+        }
+        let wheels = pins
+            .iter()
+            .zip(WHEEL_LETTERS)
+            .map(|(pins, letters)| {
+                let pins = pins.to_ascii_uppercase();
+                if pins.chars().all(|pin| letters.contains(pin)) {
+                    Pinwheel::new(
+                        letters
+                            .chars()
+                            .map(|letter| pins.contains(letter))
+                            .collect(),
+                    )
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<Pinwheel>>>()?
+            .try_into()
+            .ok()?;
+        Some(M209 {
+            wheels,
+            lugs: lugs.to_vec(),
+        })
+    }
+
+    /// parses a lug setting like `"3-6 0-6 1-6 ..."`, one pair per bar.
+    pub fn parse_lugs(lugs: &str) -> Option<Vec<(usize, usize)>> {
+        lugs.split_whitespace()
+            .map(|bar| {
+                let (a, b) = bar.split_once('-')?;
+                Some((a.parse().ok()?, b.parse().ok()?))
+            })
+            .collect()
+    }
+
+    /// sets the letters shown in the six windows, wheel 1 first.
+    pub fn set_positions(&mut self, positions: &str) -> Option<()> {
+        let positions = positions
+            .to_ascii_uppercase()
+            .chars()
+            .zip(WHEEL_LETTERS)
+            .map(|(position, letters)| letters.chars().position(|letter| letter == position))
+            .collect::<Option<Vec<usize>>>()?;
+        if positions.len() != self.wheels.len() {
+            return None;
+        }
+        self.wheels
+            .iter_mut()
+            .zip(positions)
+            .for_each(|(wheel, position)| wheel.set_position(position));
+        Some(())
+    }
+
+    pub fn positions(&self) -> String {
+        self.wheels
+            .iter()
+            .zip(WHEEL_LETTERS)
+            .map(|(wheel, letters)| letters.as_bytes()[wheel.position()] as char)
+            .collect()
+    }
+
+    /// number of bars kicked at the current position, which is the shift applied to the next letter.
+    pub fn shift(&self) -> usize {
+        let active = self
+            .wheels
+            .iter()
+            .zip(ACTIVE_OFFSET)
+            .map(|(wheel, offset)| wheel.pin(offset))
+            .collect::<Vec<bool>>();
+        self.lugs
+            .iter()
+            .filter(|(a, b)| [a, b].iter().any(|lug| **lug > 0 && active[**lug - 1]))
+            .count()
+    }
+
+    pub fn encode_char(&mut self, c: char) -> Option<char> {
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+        let plain = c.to_ascii_uppercase() as usize - 'A' as usize;
+        let encoded = ((25 - plain + self.shift()) % 26) as u8 + b'A';
+        self.wheels.iter_mut().for_each(Pinwheel::increment);
+        Some(encoded as char)
+    }
+
+    pub fn encode(&mut self, input: &str) -> String {
+        input.chars().filter_map(|c| self.encode_char(c)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::M209;
+
+    /// the check setting from the M-209 technical manual
+    fn manual_key() -> M209 {
+        let lugs = M209::parse_lugs(
+            "3-6 0-6 1-6 1-5 4-5 0-4 0-4 0-4 0-4 \
+             2-0 2-0 2-0 2-0 2-0 2-0 2-0 2-0 2-0 \
+             2-0 2-5 2-5 0-5 0-5 0-5 0-5 0-5 0-5",
+        )
+        .unwrap();
+        M209::new(
+            [
+                "ABDHIKMNSTVW",
+                "ADEGJKLORSUX",
+                "ABGHJLMNRSTUX",
+                "CEFHIMNPSTU",
+                "BDEFHIMNPS",
+                "ABDHKNOQ",
+            ],
+            &lugs,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn manual_test_message() {
+        let mut m209 = manual_key();
+        m209.set_positions("AAAAAA").unwrap();
+        assert_eq!(m209.encode(&"A".repeat(26)), "TNJUWAUQTKCZKNUTOTBCWARMIO");
+        assert_eq!(m209.positions(), "ABDFHJ");
+    }
+
+    #[test]
+    fn decodes_own_output() {
+        let mut sender = manual_key();
+        sender.set_positions("CQFTJB").unwrap();
+        let ciphertext = sender.encode("Enemy armor sighted north of the river");
+        let mut receiver = manual_key();
+        receiver.set_positions("CQFTJB").unwrap();
+        assert_eq!(
+            receiver.encode(&ciphertext),
+            "ENEMYARMORSIGHTEDNORTHOFTHERIVER"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        let lugs = vec![(0, 0); 27];
+        // W is missing on wheel 2
+        assert!(M209::new(["A", "W", "A", "A", "A", "A"], &lugs).is_none());
+        assert!(M209::new(["A"; 6], &lugs[1..]).is_none());
+        assert!(M209::new(["A"; 6], &[(7, 0); 27]).is_none());
+        let mut m209 = M209::new(["A"; 6], &lugs).unwrap();
+        // wheel 6 ends at Q
+        assert!(m209.set_positions("AAAAAZ").is_none());
+        assert_eq!(m209.shift(), 0);
+    }
+}
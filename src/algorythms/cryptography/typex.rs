@@ -0,0 +1,132 @@
+use super::enigma::{step_rotors, Reflector, Rotor};
+
+/// the British Typex. it is built from the same rotors and reflector as the Enigma,
+/// but the two rotors on the entry side (stators) never move
+/// and the moving rotors usually carry several notches.
+/// this models how the machine works, not any machine that was used: the historical
+/// rotor wirings were never published, so none are built in, and its output can not be
+/// checked against real Typex traffic. the tests use Enigma wirings as stand-ins.
+#[derive(Clone)]
+pub struct Typex {
+    stators: Vec<Rotor>,
+    rotors: Vec<Rotor>,
+    reflector: Reflector,
+}
+
+impl Typex {
+    /// both lists are given in the order the signal passes them,
+    /// so the stator next to the keyboard and the fastest rotor come first.
+    /// returns `None` unless all parts share the same alphabet.
+    pub fn new(stators: [Rotor; 2], rotors: [Rotor; 3], reflector: Reflector) -> Option<Typex> {
+        if stators
+            .iter()
+            .chain(rotors.iter())
+            .all(|rotor| rotor.alphabet() == reflector.alphabet())
+        {
+            Some(Typex {
+                stators: stators.to_vec(),
+                rotors: rotors.to_vec(),
+                reflector,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn all_rotors(&self) -> impl DoubleEndedIterator<Item = &Rotor> {
+        self.stators.iter().chain(self.rotors.iter())
+    }
+
+    /// sets the letters shown in the five windows, read from left (slowest rotor) to right (entry stator).
+    pub fn set_positions(&mut self, positions: &str) -> Option<()> {
+        let positions = self.reflector.alphabet().indices(positions)?;
+        if positions.len() != self.stators.len() + self.rotors.len() {
+            return None;
+        }
+        self.stators
+            .iter_mut()
+            .chain(self.rotors.iter_mut())
+            .zip(positions.into_iter().rev())
+            .for_each(|(rotor, position)| rotor.set_position(position));
+        Some(())
+    }
+
+    pub fn positions(&self) -> String {
+        self.all_rotors().rev().map(Rotor::window).collect()
+    }
+
+    pub fn encode_char(&mut self, c: char) -> Option<char> {
+        self.reflector.alphabet().index_of(c)?;
+        step_rotors(&mut self.rotors);
+        let forward_translated = self
+            .all_rotors()
+            .try_fold(c, |curr, rotor| rotor.forward(curr));
+        let reflected = forward_translated.and_then(|curr| self.reflector.translate(curr));
+        reflected.and_then(|reflected| {
+            self.all_rotors()
+                .rev()
+                .try_fold(reflected, |curr, rotor| rotor.backward(curr))
+        })
+    }
+
+    pub fn encode(&mut self, input: &str) -> String {
+        input.chars().filter_map(|c| self.encode_char(c)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Typex;
+    use crate::algorythms::cryptography::enigma::{Reflector, Rotor};
+
+    /// stand-in wirings, borrowed from the Enigma rotors I to V
+    fn typex() -> Typex {
+        let rotor = |wiring: &str, name: &str, notches: &str| {
+            Rotor::new(wiring, name)
+                .and_then(|rotor| rotor.with_notches(notches))
+                .unwrap()
+        };
+        Typex::new(
+            [
+                rotor("EKMFLGDQVZNTOWYHXUSPAIBRCJ", "5", ""),
+                rotor("AJDKSIRUXBLHWTMCQGZNPYFVOE", "4", ""),
+            ],
+            [
+                rotor("BDFHJLCPRTXVZNYEIWGAKMUSQO", "3", "EFMQU"),
+                rotor("ESOVPZJAYQUIRHXLNFTGKDCMWB", "2", "EFMQU"),
+                rotor("VZBRGITYUPSDNHLXAWMJQOFECK", "1", "EFMQU"),
+            ],
+            Reflector::new("YRUHQSLDPXNGOKMIEBFZCWVJAT", "B").unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_own_output() {
+        let mut sender = typex();
+        sender.set_positions("BXDQA").unwrap();
+        let ciphertext = sender.encode("Convoy sails at dawn, escort to join off Malin Head");
+        let mut receiver = typex();
+        receiver.set_positions("BXDQA").unwrap();
+        assert_eq!(
+            receiver.encode(&ciphertext),
+            "convoysailsatdawnescorttojoinoffmalinhead"
+        );
+        // a letter is never encrypted to itself
+        assert!(ciphertext
+            .chars()
+            .zip("convoysailsatdawnescorttojoinoffmalinhead".chars())
+            .all(|(c, p)| c != p));
+    }
+
+    #[test]
+    fn stators_stay_and_notches_carry() {
+        let mut typex = typex();
+        typex.set_positions("AAAQA").unwrap();
+        typex.encode(&"a".repeat(26));
+        // the fast rotor passes five notches, the middle one reaches its notches E and F
+        // and double steps on both, the stators keep their letters
+        assert_eq!(typex.positions(), "CHAQA");
+        assert!(typex.set_positions("AAAA").is_none());
+    }
+}
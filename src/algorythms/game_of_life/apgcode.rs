@@ -0,0 +1,274 @@
+//! apgcodes name objects the way Catagolue does: a prefix telling what kind of object it is,
+//! followed by its cells in extended Wechsler format, e.g. `xs4_33` for the block.
+
+use super::{
+    pattern::Pattern,
+    placement::Transform,
+    rule::Rule,
+    stability::{classify, Classification},
+    universe::Universe,
+};
+use std::fmt::Display;
+
+/// the columns of a strip of five rows, the top row being the lowest bit.
+const COLUMNS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+/// the length of a run of 4 to 39 empty columns after a `y`.
+const RUNS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+/// how long an object may take to start repeating itself.
+const MAX_GENERATIONS: u64 = 1000;
+
+/// the cells in extended Wechsler format: strips of five rows separated by `z`,
+/// each column written as one base-32 digit, runs of empty columns shortened
+/// to `w`, `x` or `y` and a count, and empty columns at the end of a strip left out.
+pub fn wechsler(cells: &[(i64, i64)]) -> String {
+    let (Some(min_x), Some(min_y)) = (
+        cells.iter().map(|(x, _)| *x).min(),
+        cells.iter().map(|(_, y)| *y).min(),
+    ) else {
+        return String::new();
+    };
+    let width = cells.iter().map(|(x, _)| x - min_x + 1).max().unwrap_or(0) as usize;
+    let height = cells.iter().map(|(_, y)| y - min_y + 1).max().unwrap_or(0) as usize;
+    let mut strips = vec![vec![0u8; width]; height.div_ceil(5)];
+    for (x, y) in cells {
+        let (x, y) = ((x - min_x) as usize, (y - min_y) as usize);
+        strips[y / 5][x] |= 1 << (y % 5);
+    }
+    strips
+        .iter()
+        .map(|columns| {
+            let mut strip = String::new();
+            let mut empty = 0;
+            for column in columns {
+                if *column == 0 {
+                    empty += 1;
+                    continue;
+                }
+                while empty > 0 {
+                    let run = empty.min(39);
+                    match run {
+                        1 => strip.push('0'),
+                        2 => strip.push('w'),
+                        3 => strip.push('x'),
+                        run => {
+                            strip.push('y');
+                            strip.push(RUNS[run - 4] as char);
+                        }
+                    }
+                    empty -= run;
+                }
+                strip.push(COLUMNS[*column as usize] as char);
+            }
+            strip
+        })
+        .collect::<Vec<String>>()
+        .join("z")
+}
+
+/// the shortest, and of those the first in alphabetical order, of the codes
+/// of every phase in every orientation.
+fn canonical(phases: &[Vec<(i64, i64)>]) -> String {
+    phases
+        .iter()
+        .flat_map(|cells| {
+            Transform::ALL.iter().map(move |transform| {
+                let cells: Vec<(i64, i64)> =
+                    cells.iter().map(|cell| transform.apply(*cell)).collect();
+                wechsler(&cells)
+            })
+        })
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .unwrap_or_default()
+}
+
+/// the apgcode of what the live cells of a pattern settle into under a Life-like rule:
+/// `xs` and the population for still lifes, `xp` and the period for oscillators
+/// and `xq` and the period for spaceships.
+/// `None` for Generations rules, rules with `B0`, and patterns that die or do not start repeating.
+pub fn encode(pattern: &Pattern, rule: Rule) -> Option<String> {
+    if rule.states() > 2 {
+        return None;
+    }
+    let mut universe = Universe::new(rule)?;
+    for (position, _) in pattern.cells.iter().filter(|(_, state)| *state == 1) {
+        universe.set_cell(*position, 1);
+    }
+    let classification = classify(&mut universe, MAX_GENERATIONS);
+    let prefix = match classification {
+        Classification::StillLife => format!("xs{}", universe.population()),
+        Classification::Oscillator { period } => format!("xp{}", period),
+        Classification::Spaceship { period, .. } => format!("xq{}", period),
+        Classification::Dead | Classification::Unknown => return None,
+    };
+    let phases: Vec<Vec<(i64, i64)>> = (0..classification.period()?)
+        .map(|_| {
+            let cells = universe.cells().map(|(position, _)| position).collect();
+            universe.step();
+            cells
+        })
+        .collect();
+    Some(format!("{}_{}", prefix, canonical(&phases)))
+}
+
+/// reads cells back from extended Wechsler format, with the top left strip at the origin.
+pub fn unwechsler(code: &str) -> Option<Vec<(i64, i64)>> {
+    let mut cells = vec![];
+    let (mut x, mut strip) = (0, 0);
+    let mut chars = code.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let run = chars.next()?;
+                x += 4 + RUNS.iter().position(|digit| *digit as char == run)? as i64;
+            }
+            'z' => (x, strip) = (0, strip + 1),
+            char => {
+                let column = COLUMNS.iter().position(|digit| *digit as char == char)?;
+                for row in 0..5 {
+                    if column & 1 << row != 0 {
+                        cells.push((x, strip * 5 + row));
+                    }
+                }
+                x += 1;
+            }
+        }
+    }
+    Some(cells)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseApgcodeError(pub String);
+
+impl Display for ParseApgcodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is not the apgcode of a still life, oscillator or spaceship",
+            self.0
+        )
+    }
+}
+
+/// the cells of an `xs`, `xp` or `xq` apgcode, as a pattern at the origin named after the code.
+/// the population of a still life has to match its cells.
+pub fn decode(apgcode: &str) -> Result<Pattern, ParseApgcodeError> {
+    let error = || ParseApgcodeError(apgcode.to_string());
+    let (prefix, code) = apgcode.split_once('_').ok_or_else(error)?;
+    let (kind, number) = (prefix.get(..2), prefix.get(2..));
+    let number = number.and_then(|number| number.parse::<u64>().ok());
+    let cells = unwechsler(code).ok_or_else(error)?;
+    match (kind, number) {
+        (Some("xs"), Some(population)) if population == cells.len() as u64 => {}
+        (Some("xp" | "xq"), Some(period)) if period > 0 => {}
+        _ => return Err(error()),
+    }
+    if cells.is_empty() {
+        return Err(error());
+    }
+    Ok(Pattern {
+        name: Some(apgcode.to_string()),
+        ..Pattern::from_cells(cells.into_iter().map(|cell| (cell, 1)).collect())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, encode, unwechsler, wechsler};
+    use crate::algorythms::game_of_life::{board::Board, rule::Rule};
+
+    fn code(pattern: &str) -> Option<String> {
+        encode(&pattern.parse().unwrap(), Rule::conway())
+    }
+
+    #[test]
+    fn encodes() {
+        for (pattern, apgcode) in [
+            ("2o$2o!", "xs4_33"),
+            ("b2o$o2bo$b2o!", "xs6_696"),
+            ("b2o$o2bo$bobo$2bo!", "xs7_2596"),
+            ("2o$obo$bo!", "xs5_253"),
+            ("3o!", "xp2_7"),
+            ("b3o$3o!", "xp2_7e"),
+            ("2o$2o$2b2o$2b2o!", "xp2_318c"),
+            ("bo$2bo$3o!", "xq4_153"),
+            ("bo2bo$o$o3bo$4o!", "xq4_6frc"),
+            // the pulsar needs more than one strip
+            (
+                "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+                "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401",
+            ),
+        ] {
+            assert_eq!(code(pattern).as_deref(), Some(apgcode), "{}", pattern);
+        }
+        // every orientation and phase gives the same code
+        assert_eq!(code("3o$o$bo!").as_deref(), Some("xq4_153"));
+        assert_eq!(code("o$o$o!").as_deref(), Some("xp2_7"));
+        // a pre-block settles into a block
+        assert_eq!(code("2o$bo!").as_deref(), Some("xs4_33"));
+        assert_eq!(code("o!"), None);
+        assert_eq!(
+            encode(&"3o!".parse().unwrap(), "B2/S/C3".parse().unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn runs_of_empty_columns() {
+        let row = |columns: &[i64]| columns.iter().map(|x| (*x, 0)).collect::<Vec<_>>();
+        assert_eq!(wechsler(&row(&[0, 2])), "101");
+        assert_eq!(wechsler(&row(&[0, 3])), "1w1");
+        assert_eq!(wechsler(&row(&[0, 4])), "1x1");
+        assert_eq!(wechsler(&row(&[0, 5])), "1y01");
+        assert_eq!(wechsler(&row(&[0, 40])), "1yz1");
+        assert_eq!(wechsler(&row(&[0, 41])), "1yz01");
+        // an empty strip in between
+        assert_eq!(wechsler(&[(0, 0), (0, 10)]), "1zz1");
+        assert_eq!(wechsler(&[]), "");
+    }
+
+    #[test]
+    fn decodes() {
+        for apgcode in [
+            "xs4_33",
+            "xs6_696",
+            "xp2_7",
+            "xp2_318c",
+            "xq4_153",
+            "xq4_6frc",
+            "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401",
+        ] {
+            let pattern = decode(apgcode).unwrap();
+            assert_eq!(pattern.name.as_deref(), Some(apgcode));
+            assert_eq!(encode(&pattern, Rule::conway()).as_deref(), Some(apgcode));
+        }
+        assert_eq!(
+            decode("xq4_153").unwrap().positions(),
+            vec![(0, 0), (1, 0), (2, 0), (2, 1), (1, 2)]
+        );
+        // a glider the other way around still encodes to the canonical code
+        let flipped = decode("xq4_742").unwrap();
+        assert_eq!(encode(&flipped, Rule::conway()).as_deref(), Some("xq4_153"));
+
+        let mut board = Board::new((0, 0), (8, 8));
+        board.fill();
+        board.place_pattern(&decode("xs6_696").unwrap());
+        assert_eq!(
+            board.to_pattern(&board.dimensions).positions(),
+            decode("xs6_696").unwrap().positions()
+        );
+
+        for invalid in [
+            "xs4", "xs5_33", "xs_33", "yl4_33", "xp0_7", "xp2_7!", "xs0_", "xp2_y",
+        ] {
+            assert!(decode(invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!(
+            decode("xs5_33").unwrap_err().to_string(),
+            "xs5_33 is not the apgcode of a still life, oscillator or spaceship"
+        );
+        assert_eq!(unwechsler("1yz01"), Some(vec![(0, 0), (41, 0)]));
+        assert_eq!(unwechsler("1zz1"), Some(vec![(0, 0), (0, 10)]));
+    }
+}
@@ -1,18 +1,33 @@
 use super::{
+    pattern::Pattern,
     patterns::Patterns,
     pixel::{Pixel, PixelPosition, PixelState},
+    rule::Rule,
+    soup::{symmetric_cells, Symmetry},
+    topology::Topology,
 };
 use rand;
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     convert::From,
     hash::Hash,
-    ops::{Add, Range, Sub},
+    ops::Range,
     rc::Rc,
 };
 use terminal_size::{terminal_size, Height, Width};
 
+const NEIGHBOURHOOD: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
 pub struct BoardDimensions<T> {
     x_max: T,
     x_min: T,
@@ -44,6 +59,9 @@ where
 pub struct Board<T> {
     pixels: HashMap<PixelPosition<T>, Rc<RefCell<Pixel<T>>>>,
     pub dimensions: BoardDimensions<T>,
+    rule: Rule,
+    topology: Topology,
+    generation: u64,
 }
 
 impl<T> Board<T>
@@ -54,8 +72,8 @@ where
         + Clone
         + Copy
         + From<<Range<T> as Iterator>::Item>
-        + Sub<u8, Output = T>
-        + Add<u8, Output = T>,
+        + Into<i64>
+        + TryFrom<i64>,
     Range<T>: Iterator,
     <Range<T> as Iterator>::Item: Copy,
 {
@@ -63,9 +81,46 @@ where
         Board {
             pixels: HashMap::new(),
             dimensions: BoardDimensions::new(min, max),
+            rule: Rule::default(),
+            topology: Topology::default(),
+            generation: 0,
         }
     }
 
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// the number of ticks since the board was created.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// picks how the edges are joined. set it before [`Board::fill`],
+    /// or call [`Board::set_topology`] to relink the pixels afterwards.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.register_neighbours();
+    }
+
     pub fn get_pixel(&self, position: (T, T)) -> Option<Rc<RefCell<Pixel<T>>>> {
         match self.pixels.get(&PixelPosition::new(position)) {
             Some(px) => Some(Rc::clone(px)),
@@ -86,59 +141,69 @@ where
         self.pixels.shrink_to_fit();
     }
 
-    pub fn randomize(&mut self) {
-        self.pixels.iter_mut().for_each(|(_pos, pixel)| {
+    /// brings half of the pixels to life at random and returns the seed,
+    /// which gives the same board again with [`Board::randomize_with`].
+    pub fn randomize(&mut self) -> u64 {
+        let seed = rand::random();
+        self.randomize_with(seed, 0.5, Symmetry::C1);
+        seed
+    }
+
+    /// fills the board with a soup of the given seed, density and symmetry,
+    /// mirrored or turned around the centre of the board.
+    pub fn randomize_with(&mut self, seed: u64, density: f64, symmetry: Symmetry) {
+        let x_min: i64 = self.dimensions.x_min.into();
+        let y_min: i64 = self.dimensions.y_min.into();
+        let size = (
+            (self.dimensions.x_max.into() - x_min) as usize,
+            (self.dimensions.y_max.into() - y_min) as usize,
+        );
+        let cells: HashSet<(i64, i64)> = symmetric_cells(seed, size, density, symmetry)
+            .into_iter()
+            .collect();
+        for pixel in self.pixels.values() {
             let mut pixel = pixel.borrow_mut();
-            pixel.set_state(rand::random());
-            pixel.commit_state()
-        })
+            let position = *pixel.get_postion();
+            let cell = (
+                (*position.get_x()).into() - x_min,
+                (*position.get_y()).into() - y_min,
+            );
+            pixel.set_state(match cells.contains(&cell) {
+                true => PixelState::Alive,
+                false => PixelState::Dead,
+            });
+            pixel.commit_state();
+        }
     }
 
+    /// links every pixel to the up to 8 pixels around it, wrapping around the edges
+    /// the way the board's topology joins them.
     pub fn register_neighbours(&mut self) {
+        let x_min: i64 = self.dimensions.x_min.into();
+        let y_min: i64 = self.dimensions.y_min.into();
+        let size = (
+            self.dimensions.x_max.into() - x_min,
+            self.dimensions.y_max.into() - y_min,
+        );
         for pixel in self.pixels.values() {
-            // let mut pixel = pixel.borrow_mut();
-            let position = pixel.borrow().get_postion().clone();
-
-            let mut pixel_positions = vec![];
-
-            let x = position.get_x().clone();
-            let y = position.get_y().clone();
-
-            let allow_top = y != self.dimensions.y_min;
-            let allow_bottom = y != self.dimensions.y_max;
-            let allow_left = x != self.dimensions.x_min;
-            let allow_right = x != self.dimensions.x_max;
+            let position = *pixel.borrow().get_postion();
+            let x = (*position.get_x()).into() - x_min;
+            let y = (*position.get_y()).into() - y_min;
+            pixel.borrow_mut().clear_neighbours();
 
-            if allow_top {
-                pixel_positions.push((x, y - 1));
-                if allow_left {
-                    pixel_positions.push((x - 1, y - 1))
+            for (dx, dy) in NEIGHBOURHOOD {
+                let Some((nx, ny)) = self.topology.wrap((x + dx, y + dy), size) else {
+                    continue;
+                };
+                // tiny grids can wrap a cell onto itself
+                if (nx, ny) == (x, y) {
+                    continue;
                 }
-                if allow_right {
-                    pixel_positions.push((x + 1, y - 1))
-                }
-            }
-            if allow_bottom {
-                pixel_positions.push((x, y + 1));
-                if allow_left {
-                    pixel_positions.push((x - 1, y + 1))
-                }
-                if allow_right {
-                    pixel_positions.push((x + 1, y + 1))
-                }
-            }
-
-            if allow_left {
-                pixel_positions.push((x - 1, y))
-            }
-            if allow_right {
-                pixel_positions.push((x + 1, y))
-            }
-
-            for (x, y) in pixel_positions {
-                if let Some(neighbour) = self.get_pixel((x, y)) {
-                    let nbv = Rc::clone(&neighbour);
-                    pixel.borrow_mut().register_neighbour(nbv)
+                let (Ok(nx), Ok(ny)) = (T::try_from(nx + x_min), T::try_from(ny + y_min)) else {
+                    continue;
+                };
+                if let Some(neighbour) = self.get_pixel((nx, ny)) {
+                    pixel.borrow_mut().register_neighbour(neighbour)
                 }
             }
         }
@@ -149,11 +214,8 @@ where
         for pixel in self.pixels.values_mut() {
             let mut pixel = pixel.borrow_mut();
             // println!("x:{}, y:{}, alive_neighbours: {}", pixel.get_postion().get_x(), pixel.get_postion().get_y(), pixel.count_alive_neighbours())
-            let new_state = match (pixel.count_alive_neighbours(), pixel.get_state()) {
-                (3, _) => PixelState::Alive,
-                (2, curr) => curr.clone(),
-                (_, _) => PixelState::Dead,
-            };
+            let state = u8::from(*pixel.get_state());
+            let new_state = PixelState::from(self.rule.next(state, pixel.count_alive_neighbours()));
             if &new_state != pixel.get_state() && !has_movement {
                 has_movement = true
             }
@@ -162,12 +224,14 @@ where
         for pixel in self.pixels.values_mut() {
             pixel.borrow_mut().commit_state();
         }
+        self.generation += 1;
         has_movement
     }
 
+    /// whether any cell is alive or still dying.
     pub fn has_live(&self) -> bool {
         self.pixels.values().fold(false, |has_live, pixel| {
-            if pixel.borrow().get_state() == &PixelState::Alive {
+            if pixel.borrow().get_state() != &PixelState::Dead {
                 true
             } else {
                 has_live
@@ -182,12 +246,15 @@ where
 
 impl Board<u8> {
     pub fn fmt(&self) -> String {
+        if self.rule.states() > 2 {
+            return self.fmt_states();
+        }
         let mut rows = vec![];
         let (max_width, max_height) =
             terminal_size().unwrap_or((Width(u16::MAX), Height(u16::MAX)));
 
         for y in self.dimensions.iter_y().step_by(2) {
-            if y as u16 > max_height.0 * 2 - 1 {
+            if y as usize >= max_height.0 as usize * 2 {
                 break;
             }
             let mut row = String::new();
@@ -206,9 +273,9 @@ impl Board<u8> {
                             .unwrap_or(PixelState::default()),
                     ) {
                         (PixelState::Alive, PixelState::Alive) => '█',
-                        (PixelState::Alive, PixelState::Dead) => '▀',
-                        (PixelState::Dead, PixelState::Alive) => '▄',
-                        (PixelState::Dead, PixelState::Dead) => ' ',
+                        (PixelState::Alive, _) => '▀',
+                        (_, PixelState::Alive) => '▄',
+                        (_, _) => ' ',
                     },
                 )
             }
@@ -216,14 +283,128 @@ impl Board<u8> {
         }
         rows.join("\n")
     }
+    /// draws one cell per character, so that the dying states of a Generations rule
+    /// can be told apart by getting lighter as they age.
+    fn fmt_states(&self) -> String {
+        const SHADES: [char; 3] = ['▓', '▒', '░'];
+        let (Width(max_width), Height(max_height)) =
+            terminal_size().unwrap_or((Width(u16::MAX), Height(u16::MAX)));
+        let dying_states = self.rule.states() as usize - 2;
+        self.dimensions
+            .iter_y()
+            .take(max_height as usize)
+            .map(|y| {
+                self.dimensions
+                    .iter_x()
+                    .take(max_width as usize)
+                    .map(
+                        |x| match self.get_pixel((x, y)).map(|px| *px.borrow().get_state()) {
+                            Some(PixelState::Alive) => '█',
+                            Some(PixelState::Dying(state)) => {
+                                SHADES[(state as usize - 2) * SHADES.len() / dying_states]
+                            }
+                            _ => ' ',
+                        },
+                    )
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// the cells within `region` that are not dead as a pattern, moved to the origin,
+    /// e.g. to save them as RLE.
+    /// pass `&board.dimensions` to export the whole board.
+    pub fn to_pattern(&self, region: &BoardDimensions<u8>) -> Pattern {
+        let mut cells = vec![];
+        for y in region.iter_y() {
+            for x in region.iter_x() {
+                if let Some(pixel) = self.get_pixel((x, y)) {
+                    let state = u8::from(*pixel.borrow().get_state());
+                    if state != 0 {
+                        cells.push(((x as i64, y as i64), state));
+                    }
+                }
+            }
+        }
+        Pattern {
+            rule: Some(self.rule.to_string()),
+            ..Pattern::from_cells(cells)
+        }
+    }
+
+    /// the pixels that are not dead, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = ((u8, u8), u8)> + '_ {
+        self.pixels.values().filter_map(|pixel| {
+            let pixel = pixel.borrow();
+            let state = u8::from(*pixel.get_state());
+            let position = pixel.get_postion();
+            (state != 0).then_some(((*position.get_x(), *position.get_y()), state))
+        })
+    }
+
     pub fn create_life(&mut self, pattern: Patterns) {
-        for (x, y) in pattern.get_life_positions() {
+        self.place_pattern(&pattern.pattern())
+    }
+
+    /// makes the board `width` by `height` pixels from its top left corner, dropping the pixels
+    /// that are left outside and adding the new ones. `false` if the corner is too close to the
+    /// end of the coordinates for that size, leaving the board as it was.
+    pub fn resize(&mut self, (width, height): (usize, usize)) -> bool {
+        let x_min: i64 = self.dimensions.x_min.into();
+        let y_min: i64 = self.dimensions.y_min.into();
+        let max = |min: i64, size: usize| {
+            i64::try_from(size)
+                .ok()
+                .and_then(|size| min.checked_add(size))
+                .and_then(|max| u8::try_from(max).ok())
+        };
+        let (Some(x_max), Some(y_max)) = (max(x_min, width), max(y_min, height)) else {
+            return false;
+        };
+        self.dimensions = BoardDimensions::new(
+            (self.dimensions.x_min, self.dimensions.y_min),
+            (x_max, y_max),
+        );
+        let (x_range, y_range) = (self.dimensions.iter_x(), self.dimensions.iter_y());
+        self.pixels.retain(|position, _| {
+            let (x, y) = (*position.get_x(), *position.get_y());
+            x_range.start <= x && x < x_range.end && y_range.start <= y && y < y_range.end
+        });
+        self.fill();
+        true
+    }
+
+    /// brings the cells of a pattern to life, in their states, at its position, or the origin.
+    /// cells that fall outside of the `u8` range, or in states the rule does not have, are left out.
+    /// if the pattern names a Life-like rule, the board switches to it. a bounded grid in the rule
+    /// resizes the board to it and joins its edges the grid's way; a grid the board cannot be
+    /// resized to is a mismatch, and the board keeps its rule and topology.
+    pub fn place_pattern(&mut self, pattern: &Pattern) {
+        if let Some(Ok(rule)) = pattern.rule.as_deref().map(str::parse::<Rule>) {
+            match rule.grid() {
+                Some(grid) if self.resize((grid.width, grid.height)) => {
+                    self.set_topology(grid.topology);
+                    self.rule = rule;
+                }
+                Some(_) => {}
+                None => self.rule = rule,
+            }
+        }
+        let states = self.rule.states();
+        let cells = pattern
+            .placed_cells()
+            .filter(|(_, state)| *state < states)
+            .filter_map(|((x, y), state)| {
+                Some(((u8::try_from(x).ok()?, u8::try_from(y).ok()?), state))
+            });
+        for ((x, y), state) in cells {
             let mut pixel = self
                 .pixels
                 .entry(PixelPosition::new((x, y)))
                 .or_insert(Rc::new(RefCell::new(Pixel::new((x, y)))))
                 .borrow_mut();
-            pixel.set_state(PixelState::Alive);
+            pixel.set_state(PixelState::from(state));
             pixel.commit_state();
         }
     }
@@ -234,14 +415,26 @@ impl Default for Board<u8> {
         Board {
             pixels: HashMap::new(),
             dimensions: BoardDimensions::new((u8::MIN, u8::MIN), (u8::MAX, u8::MAX)),
+            rule: Rule::default(),
+            topology: Topology::default(),
+            generation: 0,
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::super::patterns::Patterns;
-    use super::Board;
+    use super::super::{
+        engine::LifeEngine,
+        pattern::Pattern,
+        pixel::PixelState,
+        placement::{Placement, Transform},
+        soup::Symmetry,
+        topology::Topology,
+    };
+    use super::super::{format::Format, patterns::Patterns, rle};
+    use super::{Board, BoardDimensions};
+    use crate::algorythms::game_of_life::stability::{Classification, Detector};
     use clearscreen;
     #[test]
     fn create_borad() {
@@ -249,28 +442,203 @@ mod test {
         board.fill();
         board.randomize();
         assert_eq!(board.count_pixels(), u8::MAX as usize * u8::MAX as usize);
-        while board.has_live() {
+        // a soup this big can take thousands of generations to settle down,
+        // far more than the pixels get through in a test, so only the first ones are shown
+        let mut detector = Detector::new();
+        while detector.observe(&board).is_none() && board.generation() < 50 {
             let _ = clearscreen::clear();
             println!("{}", board.fmt());
-            // std::thread::sleep(std::time::Duration::from_millis(100));
-            if !board.tick() {
-                break;
+            board.tick();
+        }
+        assert_eq!(board.count_pixels(), u8::MAX as usize * u8::MAX as usize);
+    }
+    #[test]
+    fn randomizes_with_seed() {
+        let mut board = Board::new((0, 0), (12, 12));
+        board.fill();
+        let seed = board.randomize();
+        let soup = board.to_pattern(&board.dimensions).positions();
+        board.randomize_with(seed, 0.5, Symmetry::C1);
+        assert_eq!(board.to_pattern(&board.dimensions).positions(), soup);
+
+        board.randomize_with(seed, 0.5, Symmetry::D8);
+        let cells = board.to_pattern(&board.dimensions).placed_cells().count();
+        for x in 0..12 {
+            for y in 0..12 {
+                let state = |(x, y)| *board.get_pixel((x, y)).unwrap().borrow().get_state();
+                assert!(state((x, y)) == state((11 - x, y)));
+                assert!(state((x, y)) == state((y, x)));
             }
         }
+        assert!(cells > 0);
+    }
+    #[test]
+    fn exports_rle() {
+        let mut board = Board::new((0, 0), (100, 20));
+        board.fill();
+        board.create_life(Patterns::Copperhead);
+        let text = rle::write(&board.to_pattern(&board.dimensions));
+        assert!(text.starts_with("x = 12, y = 8, rule = B3/S23\n"));
+        assert_eq!(
+            rle::parse(&text).unwrap().positions(),
+            Patterns::Copperhead.pattern().positions()
+        );
+
+        // only the left half of the ship
+        let half = board.to_pattern(&BoardDimensions::new((0, 0), (6, 20)));
+        assert_eq!(half.size, (6, 8));
+        let mut left = Patterns::Copperhead.pattern().positions();
+        left.retain(|(x, _)| *x < 6);
+        assert_eq!(half.positions(), left);
+    }
+
+    #[test]
+    fn places_turned_patterns() {
+        let mut board = Board::new((0, 0), (40, 40));
+        board.fill();
+        let placement = Placement::at((20, 5)).with_transform(Transform::Rotate90);
+        LifeEngine::place(&mut board, &Patterns::Copperhead.pattern(), placement);
+        let pattern = LifeEngine::to_pattern(&board);
+        assert_eq!(pattern.position, Some((20, 5)));
+        assert_eq!(
+            pattern.positions(),
+            Patterns::Copperhead
+                .pattern()
+                .transformed(Transform::Rotate90)
+                .positions()
+        );
+    }
+
+    #[test]
+    fn places_any_format() {
+        let glider = ".O\n..O\nOOO".parse().unwrap();
+        let text = Format::Life106.write(&glider);
+        let mut board = Board::new((0, 0), (10, 10));
+        board.fill();
+        board.place_pattern(&Format::Life106.parse(&text).unwrap());
+        assert_eq!(board.to_pattern(&board.dimensions).cells, glider.cells);
+        assert_eq!(
+            board.to_pattern(&board.dimensions).rule.as_deref(),
+            Some("B3/S23")
+        );
     }
+
+    #[test]
+    fn follows_rule() {
+        let blinker = "3o!".parse().unwrap();
+        let mut board = Board::new((0, 0), (5, 5)).with_rule("B3/S".parse().unwrap());
+        board.fill();
+        board.place_pattern(&blinker);
+        board.tick();
+        // only the births above and below the middle, nothing survives
+        assert_eq!(board.to_pattern(&board.dimensions).size, (1, 1));
+
+        let mut board = Board::new((0, 0), (5, 5));
+        board.fill();
+        board.place_pattern(&"x = 3, y = 1, rule = 23/\n3o!".parse().unwrap());
+        assert_eq!(board.rule(), &"B/S23".parse().unwrap());
+        board.tick();
+        assert_eq!(
+            board.to_pattern(&board.dimensions).positions(),
+            vec![(0, 0)]
+        );
+    }
+
+    #[test]
+    fn generations() {
+        // Brian's Brain: two live cells give birth above and below while they start dying
+        let mut board = Board::new((0, 0), (4, 4));
+        board.fill();
+        board.place_pattern(&"x = 2, y = 1, rule = B2/S/C3\n$b2A!".parse().unwrap());
+        assert_eq!(board.rule().states(), 3);
+        board.tick();
+        let text = rle::write(&board.to_pattern(&board.dimensions));
+        assert_eq!(text, "x = 2, y = 3, rule = B2/S/C3\n2A$2B$2A!\n");
+        assert_eq!(board.fmt().lines().nth(1), Some(" ▓▓ "));
+        board.tick();
+        board.tick();
+        assert!(board.has_live());
+
+        // a fourth state does not exist in Brian's Brain
+        let mut board = Board::new((0, 0), (4, 4)).with_rule("B2/S/C3".parse().unwrap());
+        board.fill();
+        board.place_pattern(
+            &"x = 2, y = 1
+AC!"
+            .parse()
+            .unwrap(),
+        );
+        assert_eq!(board.to_pattern(&board.dimensions).cells, vec![((0, 0), 1)]);
+        assert_eq!(board.fmt().lines().next(), Some("█   "));
+    }
+
+    #[test]
+    fn topologies() {
+        let glider = ".O\n..O\nOOO".parse::<Pattern>().unwrap();
+        let run = |topology: Topology| {
+            let mut board = Board::new((0, 0), (8, 8)).with_topology(topology);
+            board.fill();
+            board.place_pattern(&glider);
+            for _ in 0..32 {
+                board.tick();
+            }
+            board.to_pattern(&board.dimensions).cells
+        };
+        // after 32 generations the glider has travelled once around the torus
+        assert_eq!(run(Topology::Torus), glider.cells);
+        // on a plane it crashes into the corner and leaves a block
+        assert_eq!(run(Topology::Plane).len(), 4);
+        assert_ne!(
+            run(Topology::KleinBottle { twisted_rows: true }),
+            glider.cells
+        );
+
+        let mut board = Board::new((0, 0), (16, 16));
+        board.fill();
+        board.place_pattern(&"x = 3, y = 1, rule = B3/S23:T8,6\n3o!".parse().unwrap());
+        assert_eq!(board.topology(), &Topology::Torus);
+        // the board takes the size of the grid
+        assert_eq!(board.count_pixels(), 8 * 6);
+        assert!(board.get_pixel((8, 0)).is_none());
+        // the blinker on the top row reaches across to the bottom row
+        board.tick();
+        assert!(board.get_pixel((1, 5)).unwrap().borrow().get_state() == &PixelState::Alive);
+
+        // a grid wider than the coordinates go is not taken over
+        let mut board = Board::new((0, 0), (8, 8));
+        board.fill();
+        board.place_pattern(&"x = 3, y = 1, rule = B36/S23:T300,8\n3o!".parse().unwrap());
+        assert_eq!(board.topology(), &Topology::Plane);
+        assert_eq!(board.rule().to_string(), "B3/S23");
+        assert_eq!(board.count_pixels(), 8 * 8);
+        // nor is one that is not a grid at all
+        board.place_pattern(&"x = 3, y = 1, rule = B36/S23:é4\n3o!".parse().unwrap());
+        assert_eq!(board.rule().to_string(), "B3/S23");
+    }
+
     #[test]
     fn create_copperhead() {
         let mut board = Board::new((0, 0), (100, 20));
         board.fill();
         board.create_life(Patterns::Copperhead);
-        while board.has_live() {
+        let mut detector = Detector::new();
+        let classification = loop {
             let _ = clearscreen::clear();
             println!("{}", board.fmt());
-            std::thread::sleep(std::time::Duration::from_millis(100));
-            if !board.tick() {
-                break;
+            if let Some(classification) = detector.observe(&board) {
+                break classification;
             }
-        }
+            board.tick();
+        };
+        println!(
+            "{} after {} generations",
+            classification,
+            board.generation()
+        );
+        // the ship lies against the top wall, grows into it in its seventh generation
+        // and falls apart, the last cells dying in generation 51
+        assert_eq!(classification, Classification::Dead);
+        assert_eq!(board.generation(), 51);
     }
 
     #[test]
@@ -278,13 +646,20 @@ mod test {
         let mut board = Board::new((0, 0), (100, 100));
         board.fill();
         board.create_life(Patterns::SnarkLoop);
-        while board.has_live() {
+        let mut detector = Detector::new();
+        let classification = loop {
             let _ = clearscreen::clear();
             println!("{}", board.fmt());
-            std::thread::sleep(std::time::Duration::from_millis(100));
-            if !board.tick() {
-                break;
+            if let Some(classification) = detector.observe(&board) {
+                break classification;
             }
-        }
+            board.tick();
+        };
+        println!(
+            "{} after {} generations",
+            classification,
+            board.generation()
+        );
+        assert_eq!(classification, Classification::Oscillator { period: 43 });
     }
 }
@@ -0,0 +1,442 @@
+//! a soup search in the spirit of apgsearch: random soups are run until they settle down,
+//! and the objects they leave behind are named by their apgcode and counted.
+
+use super::{
+    apgcode,
+    pattern::Pattern,
+    rule::Rule,
+    soup::{Soup, Symmetry},
+    universe::Universe,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+    io,
+    path::Path,
+};
+
+/// the longest period that counts as settled down.
+const MAX_PERIOD: usize = 60;
+/// the number of generations the population has to repeat for.
+const WINDOW: usize = 2 * MAX_PERIOD;
+/// what objects that do not settle down on their own are counted as.
+pub const UNKNOWN: &str = "zz_UNKNOWN";
+
+/// runs the universe until its population repeats with a period of at most 60 generations,
+/// long enough that only gliders and other spaceships can still be moving.
+/// returns whether that happened within `max_generations`.
+pub fn stabilise(universe: &mut Universe, max_generations: u64) -> bool {
+    let mut history = vec![universe.population()];
+    // for every period, how many generations in a row matched the one a period earlier
+    let mut matches = [0; MAX_PERIOD + 1];
+    for _ in 0..max_generations {
+        universe.step();
+        history.push(universe.population());
+        let now = history.len() - 1;
+        for period in 1..=MAX_PERIOD.min(now) {
+            if history[now] == history[now - period] {
+                matches[period] += 1;
+            } else {
+                matches[period] = 0;
+            }
+            if matches[period] >= WINDOW {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// the cells of an object now and in each of its next [`MAX_PERIOD`] generations,
+/// or only now under a rule with `B0`, which the universe can not run.
+fn evolve(cells: &[(i64, i64)], rule: Rule) -> Vec<HashSet<(i64, i64)>> {
+    let mut generations = vec![cells.iter().copied().collect()];
+    let Some(mut universe) = Universe::new(rule) else {
+        return generations;
+    };
+    for cell in cells {
+        universe.set_cell(*cell, 1);
+    }
+    for _ in 0..MAX_PERIOD {
+        universe.step();
+        generations.push(universe.cells().map(|(position, _)| position).collect());
+    }
+    generations
+}
+
+/// whether objects run differently together than each on its own.
+fn interact(objects: &[&[(i64, i64)]], evolutions: &[&[HashSet<(i64, i64)>]], rule: Rule) -> bool {
+    let together = evolve(&objects.concat(), rule);
+    together.iter().enumerate().any(|(generation, cells)| {
+        *cells
+            != evolutions
+                .iter()
+                .flat_map(|evolution| evolution[generation].iter().copied())
+                .collect()
+    })
+}
+
+/// whether two objects ever come within two cells of each other, close enough
+/// for a dead cell to have neighbours in both.
+fn close(a: &[HashSet<(i64, i64)>], b: &[HashSet<(i64, i64)>]) -> bool {
+    a.iter().zip(b).any(|(a, b)| {
+        a.iter().any(|(x, y)| {
+            b.iter()
+                .any(|(other_x, other_y)| (x - other_x).abs() <= 2 && (y - other_y).abs() <= 2)
+        })
+    })
+}
+
+/// splits cells into the objects they belong to: groups of touching cells, joined with
+/// the groups they interact with, which run differently together than on their own.
+/// groups that only interact three or more at a time, like three cells around a dead one,
+/// are joined along with every group close to them.
+pub fn separate(cells: &[(i64, i64)], rule: Rule) -> Vec<Vec<(i64, i64)>> {
+    let mut unvisited: HashSet<(i64, i64)> = cells.iter().copied().collect();
+    let mut objects = vec![];
+    for cell in cells {
+        if !unvisited.remove(cell) {
+            continue;
+        }
+        let mut object = vec![];
+        let mut queue = VecDeque::from([*cell]);
+        while let Some((x, y)) = queue.pop_front() {
+            object.push((x, y));
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if unvisited.remove(&(x + dx, y + dy)) {
+                        queue.push_back((x + dx, y + dy));
+                    }
+                }
+            }
+        }
+        objects.push(object);
+    }
+
+    let mut evolutions: Vec<_> = objects.iter().map(|object| evolve(object, rule)).collect();
+    // joining two objects can make the result interact with a third, so start over after every join
+    while let Some((a, b)) = (0..objects.len())
+        .flat_map(|a| (a + 1..objects.len()).map(move |b| (a, b)))
+        .filter(|(a, b)| close(&evolutions[*a], &evolutions[*b]))
+        .find(|(a, b)| {
+            interact(
+                &[&objects[*a], &objects[*b]],
+                &[&evolutions[*a], &evolutions[*b]],
+                rule,
+            )
+        })
+    {
+        let object = objects.remove(b);
+        evolutions.remove(b);
+        objects[a].extend(object);
+        evolutions[a] = evolve(&objects[a], rule);
+    }
+
+    // the clusters of objects that come close to each other, none of them interacting in pairs
+    let mut clusters: Vec<Vec<usize>> = vec![];
+    for index in 0..objects.len() {
+        let (near, far): (Vec<_>, Vec<_>) = clusters.into_iter().partition(|cluster| {
+            cluster
+                .iter()
+                .any(|other| close(&evolutions[index], &evolutions[*other]))
+        });
+        clusters = far;
+        clusters.push(near.into_iter().flatten().chain([index]).collect());
+    }
+    clusters
+        .into_iter()
+        .flat_map(|cluster| {
+            let members: Vec<&[(i64, i64)]> = cluster
+                .iter()
+                .map(|index| objects[*index].as_slice())
+                .collect();
+            let member_evolutions: Vec<&[HashSet<(i64, i64)>]> = cluster
+                .iter()
+                .map(|index| evolutions[*index].as_slice())
+                .collect();
+            if cluster.len() > 2 && interact(&members, &member_evolutions, rule) {
+                vec![members.concat()]
+            } else {
+                members.into_iter().map(<[_]>::to_vec).collect()
+            }
+        })
+        .collect()
+}
+
+/// how many of every object the soups of a search left behind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Census {
+    rule: Rule,
+    symmetry: Symmetry,
+    /// the width and height of the soups.
+    size: (usize, usize),
+    soups: u64,
+    objects: HashMap<String, u64>,
+    /// the seeds of soups that did not settle down.
+    pathological: Vec<u64>,
+}
+
+impl Census {
+    pub fn new(rule: Rule) -> Census {
+        Census {
+            rule,
+            symmetry: Symmetry::C1,
+            size: (16, 16),
+            soups: 0,
+            objects: HashMap::new(),
+            pathological: vec![],
+        }
+    }
+
+    /// the symmetry of the soups and their whole size, which picks the name apgsearch
+    /// gives the symmetry. C1 unless set.
+    pub fn with_symmetry(self, symmetry: Symmetry, size: (usize, usize)) -> Census {
+        Census {
+            symmetry,
+            size,
+            ..self
+        }
+    }
+
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    /// the symmetry as written to the census file, e.g. `D8_4`.
+    pub fn catagolue_symmetry(&self) -> &'static str {
+        self.symmetry.catagolue_name(self.size)
+    }
+
+    pub fn soups(&self) -> u64 {
+        self.soups
+    }
+
+    /// how often an object was seen, by its apgcode.
+    pub fn count(&self, apgcode: &str) -> u64 {
+        self.objects.get(apgcode).copied().unwrap_or(0)
+    }
+
+    /// every object seen with its count, the most common first.
+    pub fn objects(&self) -> Vec<(&str, u64)> {
+        let mut objects: Vec<(&str, u64)> = self
+            .objects
+            .iter()
+            .map(|(apgcode, count)| (apgcode.as_str(), *count))
+            .collect();
+        objects.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        objects
+    }
+
+    pub fn pathological(&self) -> &[u64] {
+        &self.pathological
+    }
+
+    /// adds the counts of another census of the same rule and symmetry, e.g. one run on another thread.
+    pub fn merge(&mut self, other: &Census) {
+        self.soups += other.soups;
+        for (apgcode, count) in &other.objects {
+            *self.objects.entry(apgcode.clone()).or_insert(0) += count;
+        }
+        self.pathological.extend(&other.pathological);
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+/// writes the census file: a header of `@` lines, then one object per line with its count.
+impl Display for Census {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rule = self.rule.to_string().to_lowercase().replace('/', "");
+        writeln!(f, "@RULE {}", rule)?;
+        writeln!(f, "@SYMMETRY {}", self.catagolue_symmetry())?;
+        writeln!(f, "@SOUPS {}", self.soups)?;
+        writeln!(f, "@OBJECTS {}", self.objects.values().sum::<u64>())?;
+        writeln!(f)?;
+        for (apgcode, count) in self.objects() {
+            writeln!(f, "{} {}", apgcode, count)?;
+        }
+        if !self.pathological.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "@PATHOLOGICAL")?;
+            for seed in &self.pathological {
+                writeln!(f, "{}", seed)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// the settings of a soup search. soup `n` of a search is seeded with `seed + n`,
+/// so any soup in the census can be looked at again with [`SoupSearch::soup`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoupSearch {
+    pub rule: Rule,
+    pub seed: u64,
+    pub width: usize,
+    pub height: usize,
+    pub density: f64,
+    pub symmetry: Symmetry,
+    /// soups still changing after this many generations are counted as pathological.
+    pub max_generations: u64,
+}
+
+impl Default for SoupSearch {
+    fn default() -> SoupSearch {
+        SoupSearch {
+            rule: Rule::conway(),
+            seed: 0,
+            width: 16,
+            height: 16,
+            density: 0.5,
+            symmetry: Symmetry::C1,
+            max_generations: 20_000,
+        }
+    }
+}
+
+impl SoupSearch {
+    pub fn soup(&self, index: u64) -> Soup {
+        Soup {
+            seed: self.seed.wrapping_add(index),
+            width: self.width,
+            height: self.height,
+            density: self.density,
+            symmetry: self.symmetry,
+        }
+    }
+
+    /// runs a soup until it settles down and counts what is left.
+    /// under a rule with `B0`, which the universe can not run, every soup is pathological.
+    pub fn search(&self, soup: &Soup, census: &mut Census) {
+        census.soups += 1;
+        let Some(mut universe) = Universe::new(self.rule) else {
+            census.pathological.push(soup.seed);
+            return;
+        };
+        for (position, _) in soup.pattern().placed_cells() {
+            universe.set_cell(position, 1);
+        }
+        if !stabilise(&mut universe, self.max_generations) {
+            census.pathological.push(soup.seed);
+            return;
+        }
+        let cells: Vec<(i64, i64)> = universe.cells().map(|(position, _)| position).collect();
+        for object in separate(&cells, self.rule) {
+            let pattern = Pattern::from_cells(object.into_iter().map(|cell| (cell, 1)).collect());
+            let apgcode =
+                apgcode::encode(&pattern, self.rule).unwrap_or_else(|| UNKNOWN.to_string());
+            *census.objects.entry(apgcode).or_insert(0) += 1;
+        }
+    }
+
+    /// searches the first `soups` soups.
+    pub fn run(&self, soups: u64) -> Census {
+        let mut census = Census::new(self.rule).with_symmetry(self.symmetry, self.soup(0).size());
+        for index in 0..soups {
+            self.search(&self.soup(index), &mut census);
+        }
+        census
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{separate, stabilise, Census, SoupSearch};
+    use crate::algorythms::game_of_life::{rule::Rule, soup::Symmetry, universe::Universe};
+
+    #[test]
+    fn separates_objects() {
+        let cells = [
+            // a block and a blinker two cells away, which leave each other alone
+            (0, 0),
+            (1, 0),
+            (0, 1),
+            (1, 1),
+            (3, -2),
+            (4, -2),
+            (5, -2),
+            // a blinker and a cell two cells away that dies on its own
+            (10, 0),
+            (11, 0),
+            (12, 0),
+            (14, 1),
+            // three cells apart from each other that give birth to a fourth together
+            (20, 0),
+            (22, 0),
+            (21, 2),
+        ];
+        let mut objects = separate(&cells, Rule::conway());
+        objects.iter_mut().for_each(|object| object.sort_unstable());
+        objects.sort_unstable();
+        assert_eq!(
+            objects,
+            vec![
+                vec![(0, 0), (0, 1), (1, 0), (1, 1)],
+                vec![(3, -2), (4, -2), (5, -2)],
+                vec![(10, 0), (11, 0), (12, 0)],
+                vec![(14, 1)],
+                vec![(20, 0), (21, 2), (22, 0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn stabilises() {
+        let mut universe = Universe::default();
+        universe.place_pattern(&"b2o$2o$bo!".parse().unwrap());
+        assert!(!stabilise(&mut universe, 1000));
+        // the R-pentomino settles down after 1103 generations, six gliders flying off
+        assert!(stabilise(&mut universe, 2000));
+        assert!((1103..1103 + 200).contains(&universe.generation()));
+        assert_eq!(universe.population(), 116);
+    }
+
+    #[test]
+    fn counts_soups() {
+        let search = SoupSearch {
+            seed: 8,
+            width: 8,
+            height: 8,
+            ..Default::default()
+        };
+        let census = search.run(4);
+        assert_eq!(census.soups(), 4);
+        assert_eq!(census, search.run(4));
+        let objects = census.objects();
+        assert!(objects.iter().all(|(apgcode, _)| apgcode.starts_with('x')));
+        assert!(objects.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+        let mut total = Census::new(Rule::conway());
+        for index in 0..4 {
+            let mut single = Census::new(Rule::conway());
+            search.search(&search.soup(index), &mut single);
+            total.merge(&single);
+        }
+        assert_eq!(total.objects(), objects);
+
+        let text = census.to_string();
+        assert!(text.starts_with("@RULE b3s23\n@SYMMETRY C1\n@SOUPS 4\n"));
+        assert!(text.contains(&format!("\n{} {}\n", objects[0].0, objects[0].1)));
+
+        // nothing settles down in a single generation
+        let impatient = SoupSearch {
+            max_generations: 1,
+            ..search
+        };
+        assert_eq!(impatient.run(2).pathological(), [8, 9]);
+
+        let symmetric = SoupSearch {
+            symmetry: Symmetry::D8,
+            width: 4,
+            height: 4,
+            ..search
+        };
+        assert_eq!(symmetric.soup(0).size(), (8, 8));
+        let census = symmetric.run(2);
+        assert_eq!(census.symmetry(), Symmetry::D8);
+        assert_eq!(census.catagolue_symmetry(), "D8_4");
+        assert!(census.to_string().contains("\n@SYMMETRY D8_4\n"));
+    }
+}
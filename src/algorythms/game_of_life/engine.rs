@@ -0,0 +1,359 @@
+//! what every simulation backend can do, so the rest of the crate does not
+//! have to care whether the cells live in pixels, a hash map, bit planes or a quadtree.
+
+use super::{
+    board::Board, dense::DenseGrid, hashlife::HashLife, pattern::Pattern, pixel::PixelState,
+    placement::Placement, rule::Rule, universe::Universe,
+};
+use terminal_size::{terminal_size, Height, Width};
+
+pub trait LifeEngine {
+    fn rule(&self) -> &Rule;
+
+    /// the number of generations computed so far.
+    fn generation(&self) -> u64;
+
+    /// the state of a cell, 0 being dead and 1 alive.
+    fn get_cell(&self, position: (i64, i64)) -> u8;
+
+    /// backends without room for a cell, or without dying states, may ignore or clamp it.
+    fn set_cell(&mut self, position: (i64, i64), state: u8);
+
+    /// computes the given number of generations.
+    fn advance(&mut self, generations: u64);
+
+    /// the number of live cells, not counting dying ones.
+    fn population(&self) -> usize;
+
+    /// the top left and bottom right cells of the smallest box around all cells that are not dead.
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))>;
+
+    /// the cells that are not dead, in no particular order.
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), u8)> + '_>;
+
+    fn is_empty(&self) -> bool {
+        self.cells().next().is_none()
+    }
+
+    /// adds the cells of a pattern at its position, or the origin.
+    fn place_pattern(&mut self, pattern: &Pattern) {
+        for (position, state) in pattern.placed_cells() {
+            self.set_cell(position, state);
+        }
+    }
+
+    /// adds the cells of a pattern in the phase, orientation and position of the placement.
+    /// the phase is worked out under the rule the pattern names, or else the engine's rule.
+    /// returns `None`, adding nothing, if that rule has `B0` and the phase is not the first.
+    fn place(&mut self, pattern: &Pattern, placement: Placement) -> Option<()> {
+        let rule = pattern.parsed_rule().unwrap_or(*self.rule());
+        self.place_pattern(&pattern.arranged_under(rule, placement)?);
+        Some(())
+    }
+
+    /// all cells that are not dead as a pattern, placed where they are.
+    fn to_pattern(&self) -> Pattern {
+        Pattern {
+            rule: Some(self.rule().to_string()),
+            ..Pattern::placed(self.cells().collect())
+        }
+    }
+
+    /// draws the bounding box of the live cells, two rows per line, cut off at the size of the terminal.
+    fn render(&self) -> String {
+        let Some(((min_x, min_y), (max_x, max_y))) = self.bounding_box() else {
+            return String::new();
+        };
+        let (Width(width), Height(height)) =
+            terminal_size().unwrap_or((Width(u16::MAX), Height(u16::MAX)));
+        let max_x = max_x.min(min_x.saturating_add(width as i64 - 1));
+        let max_y = max_y.min(min_y.saturating_add(height as i64 * 2 - 1));
+        (min_y..=max_y)
+            .step_by(2)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(
+                        |x| match (self.get_cell((x, y)) == 1, self.get_cell((x, y + 1)) == 1) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        },
+                    )
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// the smallest box around a list of positions.
+fn bounding_box(cells: impl Iterator<Item = (i64, i64)>) -> Option<((i64, i64), (i64, i64))> {
+    cells.fold(None, |bounds, (x, y)| {
+        let ((min_x, min_y), (max_x, max_y)) = bounds.unwrap_or(((x, y), (x, y)));
+        Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y))))
+    })
+}
+
+impl LifeEngine for Board<u8> {
+    fn rule(&self) -> &Rule {
+        Board::rule(self)
+    }
+
+    fn generation(&self) -> u64 {
+        Board::generation(self)
+    }
+
+    fn get_cell(&self, (x, y): (i64, i64)) -> u8 {
+        let (Ok(x), Ok(y)) = (u8::try_from(x), u8::try_from(y)) else {
+            return 0;
+        };
+        self.get_pixel((x, y))
+            .map_or(0, |pixel| u8::from(*pixel.borrow().get_state()))
+    }
+
+    /// only sets pixels that exist, call [`Board::fill`] first, to states the rule has.
+    fn set_cell(&mut self, (x, y): (i64, i64), state: u8) {
+        let (Ok(x), Ok(y)) = (u8::try_from(x), u8::try_from(y)) else {
+            return;
+        };
+        if state >= self.rule().states() {
+            return;
+        }
+        if let Some(pixel) = self.get_pixel((x, y)) {
+            let mut pixel = pixel.borrow_mut();
+            pixel.set_state(PixelState::from(state));
+            pixel.commit_state();
+        }
+    }
+
+    fn advance(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    fn population(&self) -> usize {
+        LifeEngine::cells(self)
+            .filter(|(_, state)| *state == 1)
+            .count()
+    }
+
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        bounding_box(LifeEngine::cells(self).map(|(position, _)| position))
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), u8)> + '_> {
+        Box::new(Board::cells(self).map(|((x, y), state)| ((x as i64, y as i64), state)))
+    }
+
+    fn place_pattern(&mut self, pattern: &Pattern) {
+        Board::place_pattern(self, pattern)
+    }
+}
+
+impl LifeEngine for Universe {
+    fn rule(&self) -> &Rule {
+        Universe::rule(self)
+    }
+
+    fn generation(&self) -> u64 {
+        Universe::generation(self)
+    }
+
+    fn get_cell(&self, position: (i64, i64)) -> u8 {
+        Universe::get_cell(self, position)
+    }
+
+    fn set_cell(&mut self, position: (i64, i64), state: u8) {
+        Universe::set_cell(self, position, state)
+    }
+
+    fn advance(&mut self, generations: u64) {
+        self.step_n(generations)
+    }
+
+    fn population(&self) -> usize {
+        Universe::population(self)
+    }
+
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        Universe::bounding_box(self)
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), u8)> + '_> {
+        Box::new(Universe::cells(self))
+    }
+
+    fn place_pattern(&mut self, pattern: &Pattern) {
+        Universe::place_pattern(self, pattern)
+    }
+}
+
+impl LifeEngine for DenseGrid {
+    fn rule(&self) -> &Rule {
+        DenseGrid::rule(self)
+    }
+
+    fn generation(&self) -> u64 {
+        DenseGrid::generation(self)
+    }
+
+    fn get_cell(&self, position: (i64, i64)) -> u8 {
+        DenseGrid::get_cell(self, position)
+    }
+
+    /// dying states are treated as dead.
+    fn set_cell(&mut self, position: (i64, i64), state: u8) {
+        DenseGrid::set_cell(self, position, (state == 1) as u8)
+    }
+
+    fn advance(&mut self, generations: u64) {
+        self.step_n(generations)
+    }
+
+    fn population(&self) -> usize {
+        DenseGrid::population(self)
+    }
+
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        DenseGrid::bounding_box(self)
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), u8)> + '_> {
+        Box::new(DenseGrid::cells(self).map(|position| (position, 1)))
+    }
+}
+
+impl LifeEngine for HashLife {
+    fn rule(&self) -> &Rule {
+        HashLife::rule(self)
+    }
+
+    fn generation(&self) -> u64 {
+        HashLife::generation(self)
+    }
+
+    fn get_cell(&self, position: (i64, i64)) -> u8 {
+        HashLife::get_cell(self, position) as u8
+    }
+
+    /// dying states are treated as dead.
+    fn set_cell(&mut self, position: (i64, i64), state: u8) {
+        HashLife::set_cell(self, position, state == 1)
+    }
+
+    /// stops at jumps too big for the plane.
+    fn advance(&mut self, generations: u64) {
+        let _ = HashLife::advance(self, generations);
+    }
+
+    fn population(&self) -> usize {
+        HashLife::population(self) as usize
+    }
+
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        HashLife::bounding_box(self)
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), u8)> + '_> {
+        Box::new(
+            HashLife::cells(self)
+                .into_iter()
+                .map(|position| (position, 1)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LifeEngine;
+    use crate::algorythms::game_of_life::{
+        board::Board, dense::DenseGrid, hashlife::HashLife, pattern::Pattern, patterns::Patterns,
+        rule::Rule, topology::Topology, universe::Universe,
+    };
+
+    fn engines(rule: Rule) -> Vec<Box<dyn LifeEngine>> {
+        let mut board = Board::new((0, 0), (64, 64)).with_rule(rule);
+        board.fill();
+        vec![
+            Box::new(board),
+            Box::new(Universe::new(rule).unwrap()),
+            Box::new(
+                DenseGrid::new(64, 64, rule)
+                    .unwrap()
+                    .with_topology(Topology::Plane)
+                    .unwrap(),
+            ),
+            Box::new(HashLife::new(rule).unwrap()),
+        ]
+    }
+
+    type Snapshot = (
+        u64,
+        usize,
+        Option<((i64, i64), (i64, i64))>,
+        Vec<((i64, i64), u8)>,
+    );
+
+    fn snapshot(engine: &dyn LifeEngine) -> Snapshot {
+        let mut cells: Vec<_> = engine.cells().collect();
+        cells.sort_unstable();
+        (
+            engine.generation(),
+            engine.population(),
+            engine.bounding_box(),
+            cells,
+        )
+    }
+
+    #[test]
+    fn backends_agree() {
+        for (pattern, rule) in [
+            (Patterns::GosperGliderGun.pattern(), "B3/S23"),
+            (Patterns::Copperhead.pattern(), "B3/S23"),
+            ("x = 3, y = 3\nobo$b2o$bo!".parse().unwrap(), "B36/S23"),
+        ] {
+            let rule: Rule = rule.parse().unwrap();
+            let pattern = Pattern {
+                position: Some((10, 10)),
+                rule: None,
+                ..pattern
+            };
+            let mut engines = engines(rule);
+            for engine in engines.iter_mut() {
+                engine.place_pattern(&pattern);
+                assert_eq!(engine.to_pattern().cells, pattern.cells);
+            }
+            for generations in [1, 7, 52] {
+                let snapshots: Vec<_> = engines
+                    .iter_mut()
+                    .map(|engine| {
+                        engine.advance(generations);
+                        snapshot(engine.as_ref())
+                    })
+                    .collect();
+                assert!(snapshots.iter().all(|snapshot| *snapshot == snapshots[0]));
+                assert_eq!(engines[0].render(), engines[3].render());
+            }
+        }
+    }
+
+    #[test]
+    fn cells() {
+        for mut engine in engines(Rule::conway()) {
+            assert!(engine.is_empty());
+            assert_eq!(engine.bounding_box(), None);
+            engine.set_cell((3, 4), 1);
+            engine.set_cell((5, 60), 1);
+            assert_eq!(engine.get_cell((3, 4)), 1);
+            assert_eq!(engine.get_cell((4, 4)), 0);
+            assert_eq!(engine.bounding_box(), Some(((3, 4), (5, 60))));
+            engine.set_cell((5, 60), 0);
+            assert_eq!(engine.population(), 1);
+            engine.advance(1);
+            assert!(engine.is_empty());
+            assert_eq!(engine.rule(), &Rule::conway());
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::{
+    life, macrocell,
+    pattern::{ParseError, Pattern},
+    plaintext, rle,
+};
+
+/// the pattern file formats that can be read and written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Rle,
+    /// `.cells` files with `.` and `O`.
+    Plaintext,
+    Life105,
+    Life106,
+    /// Golly's `.mc` quadtree format.
+    Macrocell,
+}
+
+impl Format {
+    /// guesses the format from the first line that is not empty.
+    /// anything not recognised is taken to be RLE.
+    pub fn detect(text: &str) -> Format {
+        let first = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+        if first.starts_with("[M2]") {
+            Format::Macrocell
+        } else if first.starts_with("#Life 1.05") {
+            Format::Life105
+        } else if first.starts_with("#Life 1.06") {
+            Format::Life106
+        } else if first.starts_with('!') || first.chars().all(|c| matches!(c, '.' | 'O' | '*')) {
+            Format::Plaintext
+        } else {
+            Format::Rle
+        }
+    }
+
+    /// the extensions of pattern files, in any case.
+    pub const EXTENSIONS: [&'static str; 5] = ["rle", "cells", "lif", "life", "mc"];
+
+    /// picks the format by a file extension like `"rle"` or `"mc"`. `.lif` and `.life`
+    /// are used for both Life 1.05 and 1.06, so for those [`Format::detect`] has to tell.
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        match extension.to_ascii_lowercase().as_str() {
+            "rle" => Some(Format::Rle),
+            "cells" => Some(Format::Plaintext),
+            "mc" => Some(Format::Macrocell),
+            _ => None,
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> Result<Pattern, ParseError> {
+        match self {
+            Format::Rle => rle::parse(text),
+            Format::Plaintext => plaintext::parse(text),
+            Format::Life105 => life::parse_105(text),
+            Format::Life106 => life::parse_106(text),
+            Format::Macrocell => macrocell::parse(text),
+        }
+    }
+
+    /// writes the pattern in this format. all formats but RLE only know live and dead cells,
+    /// so any state other than dead is written as live.
+    pub fn write(&self, pattern: &Pattern) -> String {
+        match self {
+            Format::Rle => rle::write(pattern),
+            Format::Plaintext => plaintext::write(pattern),
+            Format::Life105 => life::write_105(pattern),
+            Format::Life106 => life::write_106(pattern),
+            Format::Macrocell => macrocell::write(pattern),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Format;
+    use crate::algorythms::game_of_life::pattern::Pattern;
+
+    const FORMATS: [Format; 5] = [
+        Format::Rle,
+        Format::Plaintext,
+        Format::Life105,
+        Format::Life106,
+        Format::Macrocell,
+    ];
+
+    #[test]
+    fn detects_formats() {
+        let glider = "x = 3, y = 3\nbo$2bo$3o!".parse::<Pattern>().unwrap();
+        for format in FORMATS {
+            let text = format.write(&glider);
+            assert_eq!(Format::detect(&text), format);
+            let parsed = text.parse::<Pattern>().unwrap();
+            assert_eq!(parsed.positions(), glider.positions(), "{:?}", format);
+        }
+        assert_eq!(Format::detect("\n\n.O\n..O\nOOO"), Format::Plaintext);
+        assert_eq!(Format::detect("3o$"), Format::Rle);
+        assert_eq!(Format::from_extension("MC"), Some(Format::Macrocell));
+        assert_eq!(Format::from_extension("lif"), None);
+    }
+
+    #[test]
+    fn keeps_placement() {
+        let mut pattern = Pattern::from_cells(vec![((0, 0), 1), ((40, 3), 1), ((17, 9), 1)]);
+        pattern.position = Some((-20, -5));
+        for format in [
+            Format::Rle,
+            Format::Life105,
+            Format::Life106,
+            Format::Macrocell,
+        ] {
+            let parsed = format.parse(&format.write(&pattern)).unwrap();
+            assert_eq!(parsed.position, pattern.position, "{:?}", format);
+            assert_eq!(parsed.cells, pattern.cells, "{:?}", format);
+        }
+    }
+}
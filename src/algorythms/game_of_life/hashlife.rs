@@ -0,0 +1,600 @@
+//! Gosper's HashLife: the universe is a quadtree of canonical nodes, so equal regions
+//! are stored once, and the future of every node is memoized, which lets
+//! repetitive patterns jump ahead by billions of generations.
+
+use super::{
+    macrocell::{self, Macrocell, LEAF_LEVEL},
+    pattern::{ParseError, ParseErrorKind, Pattern},
+    rule::Rule,
+};
+use std::collections::HashMap;
+
+/// the dead and the live cell, the leaves of every tree.
+const DEAD: usize = 0;
+const ALIVE: usize = 1;
+/// a root this deep already covers the whole i64 plane.
+const MAX_LEVEL: u8 = 63;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    /// nw, ne, sw and se.
+    children: [usize; 4],
+    population: u64,
+}
+
+/// a universe of a two-state rule, stored as a quadtree centred on the origin.
+pub struct HashLife {
+    nodes: Vec<Node>,
+    canonical: HashMap<[usize; 4], usize>,
+    /// the centre of a node advanced by `2^step` generations.
+    results: HashMap<(usize, u8), usize>,
+    /// the empty node of every level.
+    empty: Vec<usize>,
+    root: usize,
+    rule: Rule,
+    generation: u64,
+    step: u8,
+    max_nodes: usize,
+}
+
+impl HashLife {
+    /// returns `None` for Generations rules, which have more than two states,
+    /// and for rules with `B0`, which bring the empty space of the tree to life.
+    pub fn new(rule: Rule) -> Option<HashLife> {
+        if rule.states() > 2 || rule.births(0) {
+            return None;
+        }
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+        let mut hashlife = HashLife {
+            nodes: vec![leaf(0), leaf(1)],
+            canonical: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            rule,
+            generation: 0,
+            step: 0,
+            max_nodes: 1 << 22,
+        };
+        hashlife.root = hashlife.empty(LEAF_LEVEL as u8);
+        Some(hashlife)
+    }
+
+    /// loads a macrocell file straight into the tree, without listing its cells.
+    pub fn from_macrocell(text: &str) -> Result<HashLife, ParseError> {
+        let Macrocell { nodes, rule, .. } = macrocell::read(text)?;
+        let rule = match rule {
+            Some(rule) => rule.parse().ok(),
+            None => Some(Rule::default()),
+        };
+        let mut hashlife = rule.and_then(HashLife::new).ok_or_else(|| {
+            let rule = text
+                .lines()
+                .find(|line| line.starts_with("#R"))
+                .unwrap_or("");
+            ParseError::new(0, 0, ParseErrorKind::UnsupportedRule(rule.to_string()))
+        })?;
+
+        // line numbers of the file to nodes of the tree, 0 being empty
+        let mut ids = vec![DEAD];
+        for node in &nodes {
+            let id = match node {
+                macrocell::Node::Leaf(cells) => {
+                    let mut leaf = hashlife.empty(LEAF_LEVEL as u8);
+                    for (x, y) in cells {
+                        leaf = hashlife.set(leaf, (*x, *y), true);
+                    }
+                    leaf
+                }
+                macrocell::Node::Branch(level, children) => {
+                    let children = children.map(|child| match child {
+                        0 => hashlife.empty(*level as u8 - 1),
+                        child => ids[child],
+                    });
+                    hashlife.join(children)
+                }
+            };
+            ids.push(id);
+        }
+        if let Some(root) = macrocell::level_of(&nodes, nodes.len()) {
+            if root as u8 <= MAX_LEVEL {
+                hashlife.root = ids[nodes.len()];
+            }
+        }
+        Ok(hashlife)
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// every call to [`HashLife::step`] advances `2^step` generations.
+    pub fn set_step(&mut self, step: u8) {
+        // the root needs three levels more than the step, and no more than the plane holds
+        self.step = step.min(MAX_LEVEL - 3);
+    }
+
+    pub fn step_size(&self) -> u8 {
+        self.step
+    }
+
+    /// the number of nodes after which [`HashLife::step`] collects garbage.
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = max_nodes;
+    }
+
+    /// the number of nodes currently held, reachable or not.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    fn join(&mut self, children: [usize; 4]) -> usize {
+        if let Some(id) = self.canonical.get(&children) {
+            return *id;
+        }
+        let node = Node {
+            level: self.nodes[children[0]].level + 1,
+            children,
+            population: children
+                .iter()
+                .map(|child| self.nodes[*child].population)
+                .sum(),
+        };
+        self.nodes.push(node);
+        self.canonical.insert(children, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    fn empty(&mut self, level: u8) -> usize {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().unwrap();
+            let node = self.join([below; 4]);
+            self.empty.push(node);
+        }
+        self.empty[level as usize]
+    }
+
+    fn children(&self, node: usize) -> [usize; 4] {
+        self.nodes[node].children
+    }
+
+    /// half the width of the root, which covers `-half..half` in both directions.
+    fn half(&self) -> i64 {
+        1 << (self.nodes[self.root].level - 1)
+    }
+
+    fn contains(&self, (x, y): (i64, i64)) -> bool {
+        let half = self.half();
+        (-half..half).contains(&x) && (-half..half).contains(&y)
+    }
+
+    /// wraps the root into one twice as wide, keeping it centred.
+    fn expand(&mut self) {
+        let level = self.nodes[self.root].level;
+        let empty = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.children(self.root);
+        let children = [
+            self.join([empty, empty, empty, nw]),
+            self.join([empty, empty, ne, empty]),
+            self.join([empty, sw, empty, empty]),
+            self.join([se, empty, empty, empty]),
+        ];
+        self.root = self.join(children);
+    }
+
+    /// a copy of `node` with the cell at `(x, y)`, relative to its top left corner, set.
+    fn set(&mut self, node: usize, (x, y): (i64, i64), alive: bool) -> usize {
+        let level = self.nodes[node].level;
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1 << (level - 1);
+        let index = (y >= half) as usize * 2 + (x >= half) as usize;
+        let mut children = self.children(node);
+        children[index] = self.set(children[index], (x % half, y % half), alive);
+        self.join(children)
+    }
+
+    pub fn get_cell(&self, (x, y): (i64, i64)) -> bool {
+        if !self.contains((x, y)) {
+            return false;
+        }
+        let half = self.half();
+        let (mut node, mut x, mut y) = (self.root, x + half, y + half);
+        while self.nodes[node].level > 0 {
+            let half = 1 << (self.nodes[node].level - 1);
+            let index = (y >= half) as usize * 2 + (x >= half) as usize;
+            (node, x, y) = (self.children(node)[index], x % half, y % half);
+        }
+        node == ALIVE
+    }
+
+    /// cells 2^62 or more away from the origin, beyond the biggest root, are ignored.
+    pub fn set_cell(&mut self, position: (i64, i64), alive: bool) {
+        while !self.contains(position) && self.nodes[self.root].level < MAX_LEVEL {
+            self.expand();
+        }
+        if !self.contains(position) {
+            return;
+        }
+        let half = self.half();
+        self.root = self.set(self.root, (position.0 + half, position.1 + half), alive);
+    }
+
+    /// adds the live cells of a two-state pattern at its position, or the origin.
+    pub fn place_pattern(&mut self, pattern: &Pattern) {
+        for (position, _) in pattern.placed_cells() {
+            self.set_cell(position, true);
+        }
+    }
+
+    fn collect_cells(&self, node: usize, corner: (i64, i64), cells: &mut Vec<(i64, i64)>) {
+        let Node {
+            level,
+            children,
+            population,
+        } = self.nodes[node];
+        if population == 0 {
+            return;
+        }
+        if level == 0 {
+            cells.push(corner);
+            return;
+        }
+        let half = 1 << (level - 1);
+        let corners = [(0, 0), (half, 0), (0, half), (half, half)];
+        for (child, (dx, dy)) in children.iter().zip(corners) {
+            self.collect_cells(*child, (corner.0 + dx, corner.1 + dy), cells);
+        }
+    }
+
+    /// the positions of all live cells.
+    pub fn cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = vec![];
+        let half = self.half();
+        self.collect_cells(self.root, (-half, -half), &mut cells);
+        cells
+    }
+
+    pub fn to_pattern(&self) -> Pattern {
+        Pattern {
+            rule: Some(self.rule.to_string()),
+            ..Pattern::placed(self.cells().into_iter().map(|cell| (cell, 1)).collect())
+        }
+    }
+
+    /// the top left and bottom right live cells.
+    pub fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        let cells = self.cells();
+        let first = *cells.first()?;
+        Some(cells.iter().fold(
+            (first, first),
+            |((min_x, min_y), (max_x, max_y)), (x, y)| {
+                (
+                    (min_x.min(*x), min_y.min(*y)),
+                    (max_x.max(*x), max_y.max(*y)),
+                )
+            },
+        ))
+    }
+
+    /// the middle half of a node, one level down.
+    fn centre(&mut self, node: usize) -> usize {
+        let [nw, ne, sw, se] = self.children(node);
+        self.join([
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ])
+    }
+
+    /// the centre of a 4x4 node after one generation, worked out cell by cell.
+    fn base_case(&mut self, node: usize) -> usize {
+        let grid = |x: usize, y: usize| {
+            let quadrant = self.children(node)[y / 2 * 2 + x / 2];
+            (self.children(quadrant)[y % 2 * 2 + x % 2] == ALIVE) as u8
+        };
+        let next = [(1, 1), (2, 1), (1, 2), (2, 2)].map(|(x, y)| {
+            let neighbours = (x - 1..=x + 1)
+                .flat_map(|nx| (y - 1..=y + 1).map(move |ny| (nx, ny)))
+                .filter(|position| *position != (x, y))
+                .map(|(nx, ny)| grid(nx, ny))
+                .sum();
+            match self.rule.next(grid(x, y), neighbours) {
+                0 => DEAD,
+                _ => ALIVE,
+            }
+        });
+        self.join(next)
+    }
+
+    /// the centre of `node` advanced by `2^step` generations, where `step` is at most
+    /// two less than the level of the node.
+    fn successor(&mut self, node: usize, step: u8) -> usize {
+        if let Some(result) = self.results.get(&(node, step)) {
+            return *result;
+        }
+        let Node {
+            level, population, ..
+        } = self.nodes[node];
+        let result = if population == 0 {
+            self.empty(level - 1)
+        } else if level == 2 {
+            self.base_case(node)
+        } else {
+            let [a, b, c, d] = self.children(node);
+            let [_, a_ne, a_sw, a_se] = self.children(a);
+            let [b_nw, _, b_sw, b_se] = self.children(b);
+            let [c_nw, c_ne, _, c_se] = self.children(c);
+            let [d_nw, d_ne, d_sw, _] = self.children(d);
+            // nine overlapping nodes one level down, covering the node in a 3x3 grid
+            let nine = [
+                a,
+                self.join([a_ne, b_nw, a_se, b_sw]),
+                b,
+                self.join([a_sw, a_se, c_nw, c_ne]),
+                self.join([a_se, b_sw, c_ne, d_nw]),
+                self.join([b_sw, b_se, d_nw, d_ne]),
+                c,
+                self.join([c_ne, d_nw, c_se, d_sw]),
+                d,
+            ];
+            // at full speed both halves of the way advance 2^(level - 3) generations,
+            // otherwise the first half stands still and the second does all the work
+            let full_speed = step == level - 2;
+            let r = nine.map(|node| {
+                if full_speed {
+                    self.successor(node, level - 3)
+                } else {
+                    self.centre(node)
+                }
+            });
+            let quarters = [
+                self.join([r[0], r[1], r[3], r[4]]),
+                self.join([r[1], r[2], r[4], r[5]]),
+                self.join([r[3], r[4], r[6], r[7]]),
+                self.join([r[4], r[5], r[7], r[8]]),
+            ];
+            let step = if full_speed { level - 3 } else { step };
+            let children = quarters.map(|quarter| self.successor(quarter, step));
+            self.join(children)
+        };
+        self.results.insert((node, step), result);
+        result
+    }
+
+    /// advances `2^step` generations, see [`HashLife::set_step`]. returns `None`, without advancing, if the jump
+    /// or the pattern is too big for the plane of `i64` coordinates.
+    pub fn step(&mut self) -> Option<()> {
+        if self.nodes.len() > self.max_nodes {
+            self.collect_garbage();
+        }
+        let step = self.step;
+        // make sure the pattern sits well inside the root, so nothing can leave it in time
+        loop {
+            let level = self.nodes[self.root].level;
+            let centre = self.centre(self.root);
+            let inside = self.nodes[centre].population == self.population();
+            if level >= step + 2 && inside {
+                break;
+            }
+            // room for this and the last expansion
+            if level + 1 >= MAX_LEVEL {
+                return None;
+            }
+            self.expand();
+        }
+        if self.nodes[self.root].level >= MAX_LEVEL {
+            return None;
+        }
+        self.expand();
+        self.root = self.successor(self.root, step);
+        self.generation += 1 << step;
+        Some(())
+    }
+
+    /// advances the given number of generations, in as few power-of-two jumps as possible.
+    /// returns `None` at the first jump that does not fit on the plane. the biggest jumps
+    /// come first, so usually nothing has been advanced then.
+    pub fn advance(&mut self, generations: u64) -> Option<()> {
+        let step = self.step;
+        let done = (0..64)
+            .rev()
+            .filter(|bit| generations >> bit & 1 == 1)
+            .try_for_each(|bit| {
+                self.step = bit;
+                self.step()
+            });
+        self.step = step;
+        done
+    }
+
+    /// jumps to the given generation. returns `None` if it already lies in the past,
+    /// or lies further ahead than the plane can hold the pattern for.
+    pub fn advance_to(&mut self, generation: u64) -> Option<()> {
+        let generations = generation.checked_sub(self.generation)?;
+        self.advance(generations)
+    }
+
+    /// drops the nodes and memoized results no longer reachable from the root.
+    pub fn collect_garbage(&mut self) {
+        let mut nodes = self.nodes[..=ALIVE].to_vec();
+        let mut moved = HashMap::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+        fn copy(
+            node: usize,
+            old: &[Node],
+            nodes: &mut Vec<Node>,
+            moved: &mut HashMap<usize, usize>,
+        ) -> usize {
+            if let Some(id) = moved.get(&node) {
+                return *id;
+            }
+            let children = old[node]
+                .children
+                .map(|child| copy(child, old, nodes, moved));
+            nodes.push(Node {
+                children,
+                ..old[node]
+            });
+            moved.insert(node, nodes.len() - 1);
+            nodes.len() - 1
+        }
+        let root = copy(self.root, &self.nodes, &mut nodes, &mut moved);
+        self.canonical = nodes
+            .iter()
+            .enumerate()
+            .skip(ALIVE + 1)
+            .map(|(id, node)| (node.children, id))
+            .collect();
+        self.nodes = nodes;
+        self.root = root;
+        self.results.clear();
+        self.empty = vec![DEAD];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HashLife, MAX_LEVEL};
+    use crate::algorythms::game_of_life::{
+        format::Format, pattern::Pattern, patterns::Patterns, rule::Rule,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn step_sizes() {
+        // the gun after 256 generations, reached in jumps of any size
+        let run = |step: u8| {
+            let mut hashlife = HashLife::new(Rule::conway()).unwrap();
+            hashlife.place_pattern(&Patterns::GosperGliderGun.pattern());
+            hashlife.set_step(step);
+            while hashlife.generation() < 256 {
+                hashlife.step().unwrap();
+            }
+            assert_eq!(hashlife.generation(), 256);
+            hashlife.cells().into_iter().collect::<HashSet<_>>()
+        };
+        let cells = run(0);
+        for step in [1, 3, 8] {
+            assert_eq!(run(step), cells);
+        }
+
+        // any other number of generations is made of powers of two, keeping the step size
+        let mut hashlife = HashLife::new(Rule::conway()).unwrap();
+        hashlife.place_pattern(&Patterns::GosperGliderGun.pattern());
+        hashlife.set_step(3);
+        hashlife.advance(100).unwrap();
+        hashlife.advance(156).unwrap();
+        assert_eq!(hashlife.generation(), 256);
+        assert_eq!(hashlife.step_size(), 3);
+        assert_eq!(hashlife.cells().into_iter().collect::<HashSet<_>>(), cells);
+        hashlife.set_step(200);
+        assert_eq!(hashlife.step_size(), MAX_LEVEL - 3);
+
+        assert!(HashLife::new("B2/S/C3".parse().unwrap()).is_none());
+        // the empty plane would come to life
+        assert!(HashLife::new("B03/S23".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn jumps_far_ahead() {
+        let mut hashlife = HashLife::new(Rule::conway()).unwrap();
+        hashlife.place_pattern(&Patterns::GosperGliderGun.pattern());
+        hashlife.set_step(20);
+        hashlife.step();
+        assert_eq!(hashlife.generation(), 1 << 20);
+        let population = hashlife.population();
+        // one more glider every 30 generations
+        hashlife.advance(30);
+        assert_eq!(hashlife.population(), population + 5);
+        assert_eq!(hashlife.advance_to(1 << 40), Some(()));
+        assert_eq!(hashlife.generation(), 1 << 40);
+        assert_eq!(hashlife.advance_to(5), None);
+        // the root would need more than 64 levels
+        assert_eq!(hashlife.advance_to(1 << 62), None);
+        assert_eq!(hashlife.generation(), 1 << 40);
+        assert_eq!(hashlife.advance_to(u64::MAX), None);
+
+        // a glider moves one cell diagonally every four generations
+        let mut glider = HashLife::new(Rule::conway()).unwrap();
+        glider.place_pattern(&"bo$2bo$3o!".parse().unwrap());
+        glider.advance_to(4_000_000_000_000).unwrap();
+        assert_eq!(
+            glider.bounding_box(),
+            Some((
+                (1_000_000_000_000, 1_000_000_000_000),
+                (1_000_000_000_002, 1_000_000_000_002)
+            ))
+        );
+    }
+
+    #[test]
+    fn cells_and_garbage() {
+        let mut hashlife = HashLife::new(Rule::conway()).unwrap();
+        hashlife.set_cell((-100, 3), true);
+        hashlife.set_cell((100, -3), true);
+        assert!(hashlife.get_cell((-100, 3)));
+        assert!(!hashlife.get_cell((-100, 4)));
+        assert!(!hashlife.get_cell((i64::MAX, 0)));
+        // the far corners of the biggest root, and the cells just beyond them
+        hashlife.set_cell((-(1 << 62), (1 << 62) - 1), true);
+        assert!(hashlife.get_cell((-(1 << 62), (1 << 62) - 1)));
+        hashlife.set_cell((-(1 << 62), (1 << 62) - 1), false);
+        for position in [
+            (1 << 62, 0),
+            (i64::MAX, 0),
+            (0, i64::MIN),
+            (i64::MIN, i64::MAX),
+        ] {
+            hashlife.set_cell(position, true);
+            assert!(!hashlife.get_cell(position));
+        }
+        hashlife.set_cell((100, -3), false);
+        assert_eq!(hashlife.cells(), vec![(-100, 3)]);
+        let cells = |hashlife: &HashLife| hashlife.cells().into_iter().collect::<HashSet<_>>();
+
+        let mut gun = HashLife::new(Rule::conway()).unwrap();
+        gun.place_pattern(&Patterns::GosperGliderGun.pattern());
+        gun.advance(1000);
+        let before = (gun.node_count(), cells(&gun));
+        gun.collect_garbage();
+        assert!(gun.node_count() < before.0);
+        assert_eq!(cells(&gun), before.1);
+        // keeps running with a tiny cache
+        gun.set_max_nodes(1000);
+        gun.advance(500);
+        let mut reference = HashLife::new(Rule::conway()).unwrap();
+        reference.place_pattern(&Patterns::GosperGliderGun.pattern());
+        reference.advance(1500);
+        assert_eq!(cells(&gun), cells(&reference));
+    }
+
+    #[test]
+    fn loads_macrocell() {
+        let gun = Patterns::GosperGliderGun.pattern();
+        let text = Format::Macrocell.write(&gun);
+        let hashlife = HashLife::from_macrocell(&text).unwrap();
+        assert_eq!(
+            hashlife.cells().into_iter().collect::<HashSet<_>>(),
+            gun.placed_cells().map(|(position, _)| position).collect()
+        );
+        let pattern: Pattern = text.parse().unwrap();
+        assert_eq!(hashlife.to_pattern().cells, pattern.cells);
+        assert!(HashLife::from_macrocell("[M2]\n#R B2/S/C3\n").is_err());
+        assert!(HashLife::from_macrocell("x = 1").is_err());
+    }
+}
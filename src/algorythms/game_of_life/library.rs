@@ -0,0 +1,357 @@
+//! named patterns to place and recognise: the built in ones, and any read from a directory
+//! of pattern files, so new catalogues do not need a new build.
+
+use super::{
+    format::Format,
+    pattern::{ParseError, ParseErrorKind, Pattern},
+    patterns::Patterns,
+};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+/// the name a pattern is looked up by: lowercase, without spaces, dashes or underscores,
+/// so that `Gosper glider gun` and `gosper-glider-gun` are the same.
+pub(crate) fn key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibraryErrorKind {
+    Parse(ParseError),
+    /// a second pattern by a name, and the file of the first one.
+    DuplicateName {
+        name: String,
+        first: PathBuf,
+    },
+}
+
+/// a pattern file that could not be read or parsed, or that names a pattern a second time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryError {
+    pub path: PathBuf,
+    pub kind: LibraryErrorKind,
+}
+
+impl Display for LibraryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            LibraryErrorKind::Parse(error) => write!(f, "{}: {}", self.path.display(), error),
+            LibraryErrorKind::DuplicateName { name, first } => write!(
+                f,
+                "{}: {} is already the name of {}",
+                self.path.display(),
+                name,
+                first.display()
+            ),
+        }
+    }
+}
+
+/// patterns by name, in the order they were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternLibrary {
+    patterns: Vec<Pattern>,
+    /// where every pattern is in `patterns`, by its [`key`].
+    names: HashMap<String, usize>,
+}
+
+impl PatternLibrary {
+    pub fn new() -> PatternLibrary {
+        PatternLibrary::default()
+    }
+
+    /// the patterns that come with the crate.
+    pub fn builtin() -> PatternLibrary {
+        let mut library = PatternLibrary::new();
+        for pattern in Patterns::ALL {
+            library.insert(pattern.name(), pattern.pattern());
+        }
+        library
+    }
+
+    /// reads every `.rle`, `.cells`, `.lif`, `.life` and `.mc` file in a directory
+    /// and the directories in it, skipping other files and links to directories.
+    /// a file is read in the format its first line shows, or else the one of its extension.
+    /// a pattern is named by its `#N` line, or else by its file name without the extension.
+    /// two patterns of the same name are an error.
+    pub fn load(directory: impl AsRef<Path>) -> Result<PatternLibrary, LibraryError> {
+        let mut library = PatternLibrary::new();
+        library.load_into(directory.as_ref(), &mut HashMap::new())?;
+        Ok(library)
+    }
+
+    /// `files` is where every pattern read so far came from, by its [`key`].
+    fn load_into(
+        &mut self,
+        directory: &Path,
+        files: &mut HashMap<String, PathBuf>,
+    ) -> Result<(), LibraryError> {
+        let error = |path: &Path, err: std::io::Error| LibraryError {
+            path: path.to_path_buf(),
+            kind: LibraryErrorKind::Parse(ParseError::new(
+                0,
+                0,
+                ParseErrorKind::Io(err.to_string()),
+            )),
+        };
+        let mut entries = std::fs::read_dir(directory)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.and_then(|entry| Ok((entry.path(), entry.file_type()?))))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|err| error(directory, err))?;
+        // the same order on every system
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (path, file_type) in entries {
+            // unlike `Path::is_dir`, the type of an entry does not follow links,
+            // which could lead back up the tree
+            if file_type.is_dir() {
+                self.load_into(&path, files)?;
+                continue;
+            }
+            let extension = path
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            if !Format::EXTENSIONS.contains(&extension.as_str()) {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path).map_err(|err| error(&path, err))?;
+            // the contents tell more than the extension, unless all they show is RLE,
+            // which is what any text without a known header is taken for
+            let format = match Format::detect(&text) {
+                Format::Rle => Format::from_extension(&extension).unwrap_or(Format::Rle),
+                format => format,
+            };
+            let pattern = format.parse(&text).map_err(|error| LibraryError {
+                path: path.clone(),
+                kind: LibraryErrorKind::Parse(error),
+            })?;
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let name = pattern.name.clone().unwrap_or(stem);
+            if let Some(first) = files.insert(key(&name), path.clone()) {
+                return Err(LibraryError {
+                    path,
+                    kind: LibraryErrorKind::DuplicateName { name, first },
+                });
+            }
+            self.insert(&name, pattern);
+        }
+        Ok(())
+    }
+
+    /// adds a pattern, named `name` unless it has a name already,
+    /// and returns the one that went by that name before.
+    pub fn insert(&mut self, name: &str, pattern: Pattern) -> Option<Pattern> {
+        let name = pattern.name.clone().unwrap_or_else(|| name.to_string());
+        let pattern = Pattern {
+            name: Some(name.clone()),
+            ..pattern
+        };
+        match self.names.get(&key(&name)) {
+            Some(index) => Some(std::mem::replace(&mut self.patterns[*index], pattern)),
+            None => {
+                self.names.insert(key(&name), self.patterns.len());
+                self.patterns.push(pattern);
+                None
+            }
+        }
+    }
+
+    /// adds all patterns of another library, replacing those of the same name.
+    pub fn extend(&mut self, other: PatternLibrary) {
+        for pattern in other.patterns {
+            let name = pattern.name.clone().unwrap_or_default();
+            self.insert(&name, pattern);
+        }
+    }
+
+    /// the pattern of a name, ignoring case, spaces and dashes.
+    pub fn get(&self, name: &str) -> Option<&Pattern> {
+        self.names
+            .get(&key(name))
+            .map(|index| &self.patterns[*index])
+    }
+
+    /// the patterns whose name, author, comments or rule contain the text, ignoring case.
+    pub fn search(&self, text: &str) -> Vec<&Pattern> {
+        let text = text.to_lowercase();
+        self.patterns
+            .iter()
+            .filter(|pattern| {
+                pattern
+                    .name
+                    .iter()
+                    .chain(&pattern.author)
+                    .chain(&pattern.rule)
+                    .chain(&pattern.comments)
+                    .any(|field| field.to_lowercase().contains(&text))
+            })
+            .collect()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.patterns
+            .iter()
+            .filter_map(|pattern| pattern.name.as_deref())
+    }
+
+    /// all patterns, e.g. for a [`super::recognition::Recognizer`].
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LibraryErrorKind, PatternLibrary};
+    use crate::algorythms::game_of_life::{
+        engine::LifeEngine,
+        pattern::Pattern,
+        patterns::Patterns,
+        placement::{Placement, Transform},
+        recognition::Recognizer,
+        rule::Rule,
+        universe::Universe,
+    };
+    use std::path::PathBuf;
+
+    /// a new empty directory for a test.
+    fn directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("ttschnz-library-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("oscillators")).unwrap();
+        directory
+    }
+
+    #[test]
+    fn builtin() {
+        let library = PatternLibrary::builtin();
+        assert_eq!(library.len(), Patterns::ALL.len());
+        assert_eq!(
+            library.get("gosper-glider-gun").unwrap().cells,
+            Patterns::GosperGliderGun.pattern().cells
+        );
+        assert_eq!(
+            library.get("SNARK LOOP").unwrap().name.as_deref(),
+            Some("Snark loop")
+        );
+        assert_eq!(library.get("glider"), None);
+        assert_eq!(library.names().next(), Some("Copperhead"));
+    }
+
+    #[test]
+    fn loads_directory() {
+        let directory = directory("loads");
+        let files = [
+            (
+                "glider.rle",
+                "#N Glider\n#O Richard K. Guy\n#C the smallest spaceship\nx = 3, y = 3\nbo$2bo$3o!",
+            ),
+            ("block.cells", "OO\nOO\n"),
+            (
+                "oscillators/toad.lif",
+                "#Life 1.05\n#D Toad\n#P -1 0\n.***\n***.\n",
+            ),
+            (
+                "oscillators/blinker.rle",
+                "x = 3, y = 1, rule = B3/S23\n3o!",
+            ),
+            ("readme.txt", "not a pattern"),
+        ];
+        for (name, text) in files {
+            std::fs::write(directory.join(name), text).unwrap();
+        }
+        let mut library = PatternLibrary::load(&directory).unwrap();
+        assert_eq!(
+            library.names().collect::<Vec<_>>(),
+            ["block", "Glider", "blinker", "toad"]
+        );
+        // a `.lif` file in Life 1.05, told apart from 1.06 by its header
+        assert_eq!(library.get("toad").unwrap().cells.len(), 6);
+        assert_eq!(library.get("glider").unwrap().size, (3, 3));
+        assert_eq!(library.get("Block").unwrap().cells.len(), 4);
+        let names = |found: Vec<&Pattern>| {
+            found
+                .iter()
+                .map(|pattern| pattern.name.clone().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(library.search("guy")), ["Glider"]);
+        assert_eq!(names(library.search("SPACESHIP")), ["Glider"]);
+        assert_eq!(names(library.search("b3/s23")), ["blinker"]);
+
+        // the patterns that were read can be recognised on a board
+        let mut universe = Universe::default();
+        universe.place(
+            library.get("glider").unwrap(),
+            Placement::at((4, 4)).with_transform(Transform::Rotate180),
+        );
+        let matches = Recognizer::new(library.patterns(), Rule::conway()).find(&universe);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].name.as_deref(), Some("Glider"));
+
+        // a catalogue added to the built in ones, replacing those of the same name
+        let mut builtin = PatternLibrary::builtin();
+        let copperhead = builtin.get("copperhead").unwrap().clone();
+        library.insert("Copperhead", "3o!".parse().unwrap());
+        builtin.extend(library);
+        assert_eq!(builtin.len(), Patterns::ALL.len() + 4);
+        assert_ne!(builtin.get("copperhead"), Some(&copperhead));
+
+        // a link back up the tree is not followed
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&directory, directory.join("oscillators/up")).unwrap();
+            assert_eq!(PatternLibrary::load(&directory).unwrap().len(), 4);
+        }
+
+        // a pattern is looked up by the name it was stored under
+        let mut named = PatternLibrary::new();
+        named.insert("foo", "#N Bar\n3o!".parse().unwrap());
+        assert_eq!(named.get("foo"), None);
+        assert_eq!(named.get("bar").unwrap().name.as_deref(), Some("Bar"));
+        named.extend(named.clone());
+        assert_eq!(named.len(), 1);
+
+        std::fs::write(directory.join("oscillators/glider.rle"), "bo$2bo$3o!").unwrap();
+        let error = PatternLibrary::load(&directory).unwrap_err();
+        assert_eq!(error.path, directory.join("oscillators/glider.rle"));
+        assert_eq!(
+            error.kind,
+            LibraryErrorKind::DuplicateName {
+                name: "glider".to_string(),
+                first: directory.join("glider.rle")
+            }
+        );
+        std::fs::remove_file(directory.join("oscillators/glider.rle")).unwrap();
+
+        std::fs::write(directory.join("broken.rle"), "x = 3, y = 3\nb?o!").unwrap();
+        let error = PatternLibrary::load(&directory).unwrap_err();
+        assert_eq!(error.path, directory.join("broken.rle"));
+        assert!(error
+            .to_string()
+            .starts_with(&format!("{}: line 2", error.path.display())));
+        assert!(PatternLibrary::load(directory.join("missing")).is_err());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+//! the Life 1.05 and 1.06 formats of the old DOS Life programs.
+//! 1.05 draws blocks of `.` and `*` at the positions given by `#P` lines,
+//! 1.06 lists the coordinates of live cells, one pair per line.
+
+use super::pattern::{ParseError, ParseErrorKind, Pattern};
+
+const HEADER_105: &str = "#Life 1.05";
+const HEADER_106: &str = "#Life 1.06";
+
+fn invalid_line(index: usize, line: &str) -> ParseError {
+    ParseError::new(index + 1, 1, ParseErrorKind::InvalidLine(line.to_string()))
+}
+
+/// reads `x y` into a coordinate pair.
+fn coordinates(text: &str) -> Option<(i64, i64)> {
+    let mut numbers = text.split_whitespace().map(str::parse);
+    match (numbers.next(), numbers.next(), numbers.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Some((x, y)),
+        _ => None,
+    }
+}
+
+pub fn parse_105(text: &str) -> Result<Pattern, ParseError> {
+    let (mut comments, mut rule) = (vec![], None);
+    let mut cells = vec![];
+    let mut block = (0, 0);
+    let mut y = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(tag) = line.strip_prefix('#') {
+            let mut chars = tag.chars();
+            match (chars.next(), chars.as_str()) {
+                (Some('D' | 'C'), comment) => comments.push(comment.trim().to_string()),
+                (Some('N'), _) => rule = None,
+                (Some('R'), text) => rule = Some(text.trim().to_string()),
+                (Some('P'), text) => {
+                    block = coordinates(text).ok_or_else(|| invalid_line(index, line))?;
+                    y = 0;
+                }
+                _ if line.starts_with(HEADER_105) => {}
+                _ => return Err(invalid_line(index, line)),
+            }
+            continue;
+        }
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '.' => {}
+                '*' => cells.push(((block.0 + x as i64, block.1 + y), 1)),
+                c => {
+                    return Err(ParseError::new(
+                        index + 1,
+                        x + 1,
+                        ParseErrorKind::UnexpectedCharacter(c),
+                    ))
+                }
+            }
+        }
+        y += 1;
+    }
+    Ok(Pattern {
+        comments,
+        rule,
+        ..Pattern::placed(cells)
+    })
+}
+
+/// writes the pattern as a single block at its position, or the origin.
+pub fn write_105(pattern: &Pattern) -> String {
+    let mut text = format!("{}\n", HEADER_105);
+    if let Some(name) = &pattern.name {
+        text += &format!("#D Name: {}\n", name);
+    }
+    for comment in &pattern.comments {
+        text += &format!("#D {}\n", comment);
+    }
+    match &pattern.rule {
+        Some(rule) => text += &format!("#R {}\n", rule),
+        None => text += "#N\n",
+    }
+    let ((dx, dy), rows) = pattern.draw('.', '*');
+    let (x, y) = pattern.position.unwrap_or((0, 0));
+    text += &format!("#P {} {}\n", x + dx, y + dy);
+    for row in rows {
+        text += row.trim_end_matches('.');
+        text.push('\n');
+    }
+    text
+}
+
+pub fn parse_106(text: &str) -> Result<Pattern, ParseError> {
+    let mut comments = vec![];
+    let mut cells = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#') {
+            if !line.starts_with(HEADER_106) {
+                comments.push(comment.trim_start_matches(['D', 'C']).trim().to_string());
+            }
+        } else if !line.is_empty() {
+            let position = coordinates(line).ok_or_else(|| invalid_line(index, line))?;
+            cells.push((position, 1));
+        }
+    }
+    cells.sort_unstable();
+    cells.dedup();
+    Ok(Pattern {
+        comments,
+        ..Pattern::placed(cells)
+    })
+}
+
+pub fn write_106(pattern: &Pattern) -> String {
+    let mut text = format!("{}\n", HEADER_106);
+    for ((x, y), _) in pattern.placed_cells() {
+        text += &format!("{} {}\n", x, y);
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_105, parse_106, write_105, write_106};
+    use crate::algorythms::game_of_life::pattern::{ParseError, ParseErrorKind, Pattern};
+
+    #[test]
+    fn life_105() {
+        let text = "#Life 1.05\n#D two gliders\n#R 23/36\n#P -1 -1\n.*\n..*\n***\n#P 10 0\n*\n";
+        let pattern = parse_105(text).unwrap();
+        assert_eq!(pattern.comments, vec!["two gliders"]);
+        assert_eq!(pattern.rule.as_deref(), Some("23/36"));
+        assert_eq!(pattern.position, Some((-1, -1)));
+        assert_eq!(pattern.size, (12, 3));
+        assert_eq!(
+            pattern.positions(),
+            vec![(1, 0), (2, 1), (11, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        let written = write_105(&pattern);
+        assert!(written.starts_with("#Life 1.05\n#D two gliders\n#R 23/36\n#P -1 -1\n.*\n"));
+        assert_eq!(parse_105(&written), Ok(pattern));
+
+        // cells above the origin move the block up
+        let above = Pattern {
+            position: Some((5, 5)),
+            cells: vec![((0, -2), 1), ((0, 0), 1)],
+            ..Pattern::default()
+        };
+        assert!(write_105(&above).ends_with("#P 5 3\n*\n\n*\n"));
+        let wide: Pattern = "x = 1, y = 1\n3o!".parse().unwrap();
+        assert!(write_105(&wide).ends_with("#P 0 0\n***\n"));
+        assert!(matches!(
+            parse_105("#Life 1.05\n#P 1\n*").unwrap_err().kind,
+            ParseErrorKind::InvalidLine(_)
+        ));
+        assert_eq!(
+            parse_105("#Life 1.05\n#é\n*").unwrap_err(),
+            ParseError::new(2, 1, ParseErrorKind::InvalidLine("#é".to_string()))
+        );
+    }
+
+    #[test]
+    fn life_106() {
+        let pattern = parse_106("#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n").unwrap();
+        assert_eq!(pattern.position, Some((-1, -1)));
+        assert_eq!(
+            pattern.positions(),
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        assert_eq!(parse_106(&write_106(&pattern)), Ok(pattern));
+        let error = parse_106("#Life 1.06\n0 1\n1 x\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.kind),
+            (3, ParseErrorKind::InvalidLine("1 x".to_string()))
+        );
+    }
+}
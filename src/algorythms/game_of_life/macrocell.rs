@@ -0,0 +1,291 @@
+//! Golly's macrocell format, a quadtree written bottom up, one node per line.
+//! leaves are 8x8 blocks drawn with `.`, `*` and `$`, every other node is
+//! `level nw ne sw se` with the line numbers of its children, 0 being empty.
+//! the last node is the root, centred on the origin.
+//! see <https://conwaylife.com/wiki/Macrocell>.
+
+use super::pattern::{ParseError, ParseErrorKind, Pattern};
+use std::collections::HashMap;
+
+const HEADER: &str = "[M2]";
+/// the level of the leaves, which are 2^3 cells wide.
+pub(super) const LEAF_LEVEL: u32 = 3;
+
+pub(super) enum Node {
+    /// live cells relative to the top left corner of the leaf.
+    Leaf(Vec<(i64, i64)>),
+    Branch(u32, [usize; 4]),
+}
+
+pub(super) fn level_of(nodes: &[Node], index: usize) -> Option<u32> {
+    match nodes.get(index.checked_sub(1)?)? {
+        Node::Leaf(_) => Some(LEAF_LEVEL),
+        Node::Branch(level, _) => Some(*level),
+    }
+}
+
+fn parse_leaf(line: &str) -> Option<Vec<(i64, i64)>> {
+    let mut cells = vec![];
+    let (mut x, mut y) = (0, 0);
+    for c in line.chars() {
+        match c {
+            // a leaf is 8 by 8 cells, with every row ended by a `$`
+            '.' | '*' if x >= 8 || y >= 8 => return None,
+            '$' if y >= 8 => return None,
+            '.' => x += 1,
+            '*' => {
+                cells.push((x, y));
+                x += 1;
+            }
+            '$' => (x, y) = (0, y + 1),
+            _ => return None,
+        }
+    }
+    Some(cells)
+}
+
+fn parse_branch(line: &str, nodes: &[Node]) -> Option<Node> {
+    let numbers = line
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<usize>, _>>()
+        .ok()?;
+    let [level, nw, ne, sw, se] = numbers.as_slice() else {
+        return None;
+    };
+    let level = u32::try_from(*level).ok()?;
+    let children = [*nw, *ne, *sw, *se];
+    // children are written before their parents, one level further down
+    let fits = level > LEAF_LEVEL
+        && level < 63
+        && children
+            .iter()
+            .all(|child| *child == 0 || level_of(nodes, *child) == Some(level - 1));
+    fits.then_some(Node::Branch(level, children))
+}
+
+fn expand(nodes: &[Node], index: usize, corner: (i64, i64), cells: &mut Vec<((i64, i64), u8)>) {
+    let Some(node) = index.checked_sub(1).and_then(|index| nodes.get(index)) else {
+        return;
+    };
+    match node {
+        Node::Leaf(leaf) => {
+            cells.extend(leaf.iter().map(|(x, y)| ((corner.0 + x, corner.1 + y), 1)))
+        }
+        Node::Branch(level, children) => {
+            let half = 1 << (level - 1);
+            let corners = [(0, 0), (half, 0), (0, half), (half, half)];
+            for (child, (dx, dy)) in children.iter().zip(corners) {
+                expand(nodes, *child, (corner.0 + dx, corner.1 + dy), cells);
+            }
+        }
+    }
+}
+
+/// the nodes of a macrocell file, root last, along with its rule and comments.
+pub(super) struct Macrocell {
+    pub nodes: Vec<Node>,
+    pub rule: Option<String>,
+    pub comments: Vec<String>,
+}
+
+pub(super) fn read(text: &str) -> Result<Macrocell, ParseError> {
+    let (mut comments, mut rule) = (vec![], None);
+    let mut nodes = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let invalid =
+            || ParseError::new(index + 1, 1, ParseErrorKind::InvalidLine(line.to_string()));
+        if index == 0 {
+            if !line.starts_with(HEADER) {
+                return Err(invalid());
+            }
+        } else if let Some(tag) = line.strip_prefix('#') {
+            let mut chars = tag.chars();
+            match (chars.next(), chars.as_str()) {
+                (Some('R'), text) => rule = Some(text.trim().to_string()),
+                (_, text) => comments.push(text.trim().to_string()),
+            }
+        } else if line.starts_with(['.', '*', '$']) {
+            nodes.push(Node::Leaf(parse_leaf(line).ok_or_else(invalid)?));
+        } else if !line.is_empty() {
+            nodes.push(parse_branch(line, &nodes).ok_or_else(invalid)?);
+        }
+    }
+    Ok(Macrocell {
+        nodes,
+        rule,
+        comments,
+    })
+}
+
+pub fn parse(text: &str) -> Result<Pattern, ParseError> {
+    let Macrocell {
+        nodes,
+        rule,
+        comments,
+    } = read(text)?;
+    let mut cells = vec![];
+    if let Some(root) = level_of(&nodes, nodes.len()) {
+        let half = 1i64 << (root - 1);
+        expand(&nodes, nodes.len(), (-half, -half), &mut cells);
+    }
+    Ok(Pattern {
+        comments,
+        rule,
+        ..Pattern::placed(cells)
+    })
+}
+
+/// builds the node for the square of `2^level` cells at `corner` out of `cells`
+/// and returns its line number, reusing lines already written for the same content.
+fn build(
+    cells: &[(i64, i64)],
+    corner: (i64, i64),
+    level: u32,
+    lines: &mut Vec<String>,
+    known: &mut HashMap<String, usize>,
+) -> usize {
+    let size = 1i64 << level;
+    let inside = cells
+        .iter()
+        .copied()
+        .filter(|(x, y)| {
+            (corner.0..corner.0 + size).contains(x) && (corner.1..corner.1 + size).contains(y)
+        })
+        .collect::<Vec<(i64, i64)>>();
+    if inside.is_empty() {
+        return 0;
+    }
+    let line = if level == LEAF_LEVEL {
+        let mut rows = [['.'; 8]; 8];
+        for (x, y) in inside {
+            rows[(y - corner.1) as usize][(x - corner.0) as usize] = '*';
+        }
+        let rows = rows.map(|row| {
+            row.iter()
+                .collect::<String>()
+                .trim_end_matches('.')
+                .to_string()
+        });
+        let last = rows.iter().rposition(|row| !row.is_empty()).unwrap_or(0);
+        rows[..=last]
+            .iter()
+            .map(|row| format!("{}$", row))
+            .collect()
+    } else {
+        let half = size / 2;
+        let corners = [(0, 0), (half, 0), (0, half), (half, half)];
+        let children = corners.map(|(dx, dy)| {
+            build(
+                &inside,
+                (corner.0 + dx, corner.1 + dy),
+                level - 1,
+                lines,
+                known,
+            )
+        });
+        format!(
+            "{} {} {} {} {}",
+            level, children[0], children[1], children[2], children[3]
+        )
+    };
+    *known.entry(line.clone()).or_insert_with(|| {
+        lines.push(line);
+        lines.len()
+    })
+}
+
+/// writes the pattern with its top left corner at its position, or the origin.
+pub fn write(pattern: &Pattern) -> String {
+    let mut text = format!("{} (ttschnz)\n", HEADER);
+    if let Some(rule) = &pattern.rule {
+        text += &format!("#R {}\n", rule);
+    }
+    if let Some(name) = &pattern.name {
+        text += &format!("#C {}\n", name);
+    }
+    for comment in &pattern.comments {
+        text += &format!("#C {}\n", comment);
+    }
+    let cells = pattern
+        .placed_cells()
+        .map(|(position, _)| position)
+        .collect::<Vec<(i64, i64)>>();
+    // the root is centred on the origin, so it must reach the cell furthest from it
+    let reach = cells
+        .iter()
+        .map(|(x, y)| (x + 1).max(-x).max(y + 1).max(-y))
+        .max()
+        .unwrap_or(0);
+    let mut level = LEAF_LEVEL;
+    while 1i64 << (level - 1) < reach {
+        level += 1;
+    }
+    let half = 1i64 << (level - 1);
+    let mut lines = vec![];
+    build(
+        &cells,
+        (-half, -half),
+        level,
+        &mut lines,
+        &mut HashMap::new(),
+    );
+    for line in lines {
+        text += &line;
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, write};
+    use crate::algorythms::game_of_life::pattern::{ParseErrorKind, Pattern};
+
+    #[test]
+    fn glider() {
+        // a glider just right of and below the origin, as saved by Golly
+        let text = "[M2] (golly 4.2)\n#R B3/S23\n$$$$$.*$..*$***$\n4 0 0 0 1\n";
+        let glider = parse(text).unwrap();
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(glider.position, Some((0, 5)));
+        assert_eq!(
+            glider.positions(),
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        assert_eq!(parse(&write(&glider)), Ok(glider));
+    }
+
+    #[test]
+    fn shares_nodes() {
+        // four blocks far apart are written as a single leaf
+        let blocks = [(0, 0), (128, 0), (0, 128), (128, 128)]
+            .iter()
+            .flat_map(|(x, y)| {
+                [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(dx, dy)| ((x + dx, y + dy), 1))
+            })
+            .collect();
+        let pattern = Pattern::from_cells(blocks);
+        let text = write(&pattern);
+        assert_eq!(text.lines().filter(|line| line.contains('*')).count(), 1);
+        assert_eq!(parse(&text).unwrap().cells, pattern.cells);
+    }
+
+    #[test]
+    fn rejects_invalid_nodes() {
+        let error = parse("[M2]\n**$\n5 1 0 0 0\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.kind),
+            (3, ParseErrorKind::InvalidLine("5 1 0 0 0".to_string()))
+        );
+        assert!(parse("[M2]\n*********$\n").is_err());
+        // a ninth row would reach into the next leaf
+        assert!(parse("[M2]\n$$$$$$$$*$\n").is_err());
+        assert!(parse("[M2]\n$$$$$$$*$\n").is_ok());
+        assert!(parse("[M2]\n4 1 0 0 0\n").is_err());
+        assert!(parse("x = 1, y = 1\no!").is_err());
+        assert_eq!(parse("[M2]\n").unwrap().cells, vec![]);
+        assert_eq!(parse("[M2]\n#é comment\n").unwrap().comments, ["comment"]);
+    }
+}
@@ -1,3 +1,22 @@
+pub mod apgcode;
 pub mod board;
+pub mod census;
+pub mod dense;
+pub mod engine;
+pub mod format;
+pub mod hashlife;
+pub mod library;
+pub mod life;
+pub mod macrocell;
+pub mod pattern;
 pub mod patterns;
 pub mod pixel;
+pub mod placement;
+pub mod plaintext;
+pub mod recognition;
+pub mod rle;
+pub mod rule;
+pub mod soup;
+pub mod stability;
+pub mod topology;
+pub mod universe;
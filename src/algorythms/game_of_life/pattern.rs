@@ -0,0 +1,245 @@
+use super::{
+    format::Format,
+    placement::{Placement, Transform},
+    rule::Rule,
+    universe::Universe,
+};
+use std::{collections::HashMap, fmt::Display, io::Read, str::FromStr};
+
+/// a pattern read from a file, with cells relative to its top left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+    /// the rule given in the file, e.g. `"B3/S23"`.
+    pub rule: Option<String>,
+    /// width and height of the bounding box.
+    pub size: (usize, usize),
+    /// where the top left corner should be placed, if the file says so.
+    pub position: Option<(i64, i64)>,
+    /// every cell that is not dead, with its state. two-state patterns only use state 1.
+    pub cells: Vec<((i64, i64), u8)>,
+}
+
+impl Pattern {
+    /// a pattern of the given cells, moved so that the bounding box starts at the origin.
+    pub fn from_cells(mut cells: Vec<((i64, i64), u8)>) -> Pattern {
+        let min_x = cells.iter().map(|((x, _), _)| *x).min().unwrap_or(0);
+        let min_y = cells.iter().map(|((_, y), _)| *y).min().unwrap_or(0);
+        cells
+            .iter_mut()
+            .for_each(|((x, y), _)| (*x, *y) = (*x - min_x, *y - min_y));
+        cells.sort_by_key(|((x, y), _)| (*y, *x));
+        Pattern {
+            size: Pattern::bounding_box(&cells),
+            cells,
+            ..Default::default()
+        }
+    }
+
+    /// like [`Pattern::from_cells`], but remembers where the top left corner was,
+    /// for formats that give absolute coordinates.
+    pub fn placed(cells: Vec<((i64, i64), u8)>) -> Pattern {
+        let min_x = cells.iter().map(|((x, _), _)| *x).min();
+        let min_y = cells.iter().map(|((_, y), _)| *y).min();
+        Pattern {
+            position: min_x.zip(min_y),
+            ..Pattern::from_cells(cells)
+        }
+    }
+
+    /// the cells moved back to where the pattern is placed.
+    pub fn placed_cells(&self) -> impl Iterator<Item = ((i64, i64), u8)> + '_ {
+        let (dx, dy) = self.position.unwrap_or((0, 0));
+        self.cells
+            .iter()
+            .map(move |((x, y), state)| ((x + dx, y + dy), *state))
+    }
+
+    /// the positions of all cells that are not dead.
+    pub fn positions(&self) -> Vec<(i64, i64)> {
+        self.cells.iter().map(|(position, _)| *position).collect()
+    }
+
+    /// width and height of the smallest box around the cells.
+    pub fn bounding_box(cells: &[((i64, i64), u8)]) -> (usize, usize) {
+        let (mut max_x, mut max_y) = (-1, -1);
+        for ((x, y), _) in cells {
+            max_x = max_x.max(*x);
+            max_y = max_y.max(*y);
+        }
+        ((max_x + 1) as usize, (max_y + 1) as usize)
+    }
+
+    /// the pattern rotated or mirrored, with its bounding box back at the origin.
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let cells = self
+            .cells
+            .iter()
+            .map(|(position, state)| (transform.apply(*position), *state))
+            .collect();
+        Pattern {
+            name: self.name.clone(),
+            author: self.author.clone(),
+            comments: self.comments.clone(),
+            rule: self.rule.clone(),
+            position: self.position,
+            ..Pattern::from_cells(cells)
+        }
+    }
+
+    /// the rule the pattern names, if it can be read.
+    pub fn parsed_rule(&self) -> Option<Rule> {
+        self.rule.as_deref()?.parse().ok()
+    }
+
+    /// the pattern after `generations` generations under its own rule, or Conway's Life.
+    pub fn advanced(&self, generations: u64) -> Option<Pattern> {
+        self.advanced_under(self.parsed_rule().unwrap_or_default(), generations)
+    }
+
+    /// the pattern after `generations` generations under the given rule, whatever rule it names.
+    /// the position follows the cells, so a spaceship ends up where it flew to.
+    /// `None` for rules with `B0`, which the unbounded [`Universe`] can not run.
+    pub fn advanced_under(&self, rule: Rule, generations: u64) -> Option<Pattern> {
+        let Some(mut universe) = Universe::new(rule) else {
+            // nothing has to run for no generations
+            return (generations == 0).then(|| self.clone());
+        };
+        universe.place_pattern(&Pattern {
+            position: None,
+            rule: None,
+            ..self.clone()
+        });
+        universe.step_n(generations);
+        let advanced = Pattern::placed(universe.cells().collect());
+        let (x, y) = self.position.unwrap_or((0, 0));
+        let (dx, dy) = advanced.position.unwrap_or((0, 0));
+        Some(Pattern {
+            name: self.name.clone(),
+            author: self.author.clone(),
+            comments: self.comments.clone(),
+            rule: self.rule.clone(),
+            position: Some((x + dx, y + dy)),
+            ..advanced
+        })
+    }
+
+    /// the pattern advanced to the phase of the placement under its own rule, or Conway's Life,
+    /// transformed, and placed at its position. `None` if it can not be advanced,
+    /// see [`Pattern::advanced_under`].
+    pub fn arranged(&self, placement: Placement) -> Option<Pattern> {
+        self.arranged_under(self.parsed_rule().unwrap_or_default(), placement)
+    }
+
+    /// like [`Pattern::arranged`], advancing under the given rule.
+    pub fn arranged_under(&self, rule: Rule, placement: Placement) -> Option<Pattern> {
+        Some(Pattern {
+            position: Some(placement.position),
+            ..self
+                .advanced_under(rule, placement.phase)?
+                .transformed(placement.transform)
+        })
+    }
+
+    /// the cells of several patterns at their positions, as one pattern.
+    /// where they overlap, the later ones win. the rule is the first one given.
+    pub fn combine(patterns: &[Pattern]) -> Pattern {
+        let mut cells: HashMap<(i64, i64), u8> = HashMap::new();
+        for pattern in patterns {
+            cells.extend(pattern.placed_cells());
+        }
+        Pattern {
+            rule: patterns.iter().find_map(|pattern| pattern.rule.clone()),
+            ..Pattern::placed(cells.into_iter().collect())
+        }
+    }
+
+    /// the cells drawn as rows of text, with `alive` for cells and `dead` in between,
+    /// and where the top left character is. that is the origin, unless cells lie
+    /// above or left of it, and the rows reach as far as the size or the cells do.
+    pub fn draw(&self, dead: char, alive: char) -> ((i64, i64), Vec<String>) {
+        let (mut min_x, mut min_y) = (0, 0);
+        let (mut max_x, mut max_y) = (self.size.0 as i64 - 1, self.size.1 as i64 - 1);
+        for ((x, y), _) in &self.cells {
+            (min_x, min_y) = (min_x.min(*x), min_y.min(*y));
+            (max_x, max_y) = (max_x.max(*x), max_y.max(*y));
+        }
+        let width = (max_x - min_x + 1) as usize;
+        let mut rows = vec![vec![dead; width]; (max_y - min_y + 1) as usize];
+        for ((x, y), _) in &self.cells {
+            rows[(y - min_y) as usize][(x - min_x) as usize] = alive;
+        }
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect();
+        ((min_x, min_y), rows)
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Pattern, ParseError> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .map_err(|err| ParseError::new(0, 0, ParseErrorKind::Io(err.to_string())))?;
+        text.parse()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ParseError;
+
+    /// reads a pattern in any of the known [`Format`]s, telling them apart by their content.
+    fn from_str(text: &str) -> Result<Pattern, ParseError> {
+        Format::detect(text).parse(text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Io(String),
+    /// the `x = m, y = n` line is malformed.
+    InvalidHeader(String),
+    UnexpectedCharacter(char),
+    /// a run count that does not fit or is not followed by a cell.
+    InvalidCount(String),
+    /// a line that does not fit the format, like a coordinate pair that is not two numbers.
+    InvalidLine(String),
+    /// a multi-state prefix (`p` to `y`) that is not followed by `A` to `X`.
+    InvalidState(String),
+    /// a rule the reader cannot simulate, like a Generations rule for HashLife.
+    UnsupportedRule(String),
+    /// the input ended before the pattern was terminated.
+    UnexpectedEnd,
+}
+
+/// a problem in the input, at a one based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { line, column, kind }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match &self.kind {
+            ParseErrorKind::Io(err) => return write!(f, "could not read the pattern: {}", err),
+            ParseErrorKind::InvalidHeader(header) => format!("invalid header {}", header),
+            ParseErrorKind::UnexpectedCharacter(c) => format!("unexpected character {:?}", c),
+            ParseErrorKind::InvalidLine(line) => format!("invalid line {}", line),
+            ParseErrorKind::InvalidCount(count) => format!("invalid run count {}", count),
+            ParseErrorKind::InvalidState(state) => format!("invalid cell state {}", state),
+            ParseErrorKind::UnsupportedRule(rule) => format!("unsupported rule {}", rule),
+            ParseErrorKind::UnexpectedEnd => "unexpected end of input".to_string(),
+        };
+        write!(f, "line {}, column {}: {}", self.line, self.column, reason)
+    }
+}
@@ -1,3 +1,8 @@
+use super::{library::key, pattern::Pattern, rle};
+use std::{fmt::Display, str::FromStr};
+
+/// the patterns that come with the crate, see [`super::library::PatternLibrary`] for more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Patterns {
     Copperhead,
     SnarkLoop,
@@ -7,10 +12,28 @@ pub enum Patterns {
 }
 
 impl Patterns {
+    pub const ALL: [Patterns; 5] = [
+        Patterns::Copperhead,
+        Patterns::SnarkLoop,
+        Patterns::GosperGliderGun,
+        Patterns::SirRobin,
+        Patterns::GPT,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Copperhead => "Copperhead",
+            Self::SnarkLoop => "Snark loop",
+            Self::GosperGliderGun => "Gosper glider gun",
+            Self::SirRobin => "Sir Robin",
+            Self::GPT => "GPT",
+        }
+    }
+
     fn rle(&self) -> String {
         match self {
             Self::Copperhead => {
-                "$5bob2o$4bo6bo$3b2o3bo2bo$2obo5b2o$2obo5b2o$3b2o3bo2bo$4bo6bo$5bob2o!".to_string()
+                "5bob2o$4bo6bo$3b2o3bo2bo$2obo5b2o$2obo5b2o$3b2o3bo2bo$4bo6bo$5bob2o!".to_string()
             }
             Self::SnarkLoop =>{
                 "27b2o$27bobo$29bo4b2o$25b4ob2o2bo2bo$25bo2bo3bobob2o$28bobobobo$29b2obobo$33bo2$19b2o$20bo8bo$20bobo5b2o$21b2o$35bo$36bo$34b3o2$25bo$25b2o$24bobo4b2o22bo$31bo21b3o$32b3o17bo$34bo17b2o2$45bo$46b2o12b2o$45b2o14bo$3b2o56bob2o$4bo9b2o37bo5b3o2bo$2bo10bobo37b2o3bo3b2o$2b5o8bo5b2o35b2obo$7bo13bo22b2o15bo$4b3o12bobo21bobo12b3o$3bo15b2o22bo13bo$3bob2o35b2o5bo8b5o$b2o3bo3b2o37bobo10bo$o2b3o5bo37b2o9bo$2obo56b2o$3bo14b2o$3b2o12b2o$19bo2$11b2o17bo$12bo17b3o$9b3o21bo$9bo22b2o4bobo$38b2o$39bo2$28b3o$28bo$29bo$42b2o$35b2o5bobo$35bo8bo$44b2o2$31bo$30bobob2o$30bobobobo$27b2obobo3bo2bo$27bo2bo2b2ob4o$29b2o4bo$35bobo$36b2o!".to_string()
@@ -26,40 +49,42 @@ impl Patterns {
             }
         }
     }
+    /// the pattern, named.
+    pub fn pattern(&self) -> Pattern {
+        Pattern {
+            name: Some(self.name().to_string()),
+            ..rle::parse(&self.rle()).expect("the built in patterns are valid RLE")
+        }
+    }
+
+    /// the live cells of the pattern, leaving out those that do not fit on a `u8` board.
     pub fn get_life_positions(&self) -> Vec<(u8, u8)> {
-        let rle = self.rle();
-        let mut map = vec![];
-        let mut position = (0, 0);
-        let mut cumulated = String::new();
-        for c in rle.chars() {
-            let count = str::parse(&cumulated).unwrap_or(1);
-            match c {
-                'b' => {
-                    position.0 += count;
-                    cumulated = String::new()
-                }
-                'o' => {
-                    for x in position.0..position.0 + count {
-                        map.push((x, position.1))
-                    }
-                    position.0 += count;
-                    cumulated = String::new()
-                }
-                '$' => {
-                    position.1 += count;
-                    position.0 = 0;
+        self.pattern()
+            .positions()
+            .into_iter()
+            .filter_map(|(x, y)| Some((u8::try_from(x).ok()?, u8::try_from(y).ok()?)))
+            .collect()
+    }
+}
 
-                    cumulated = String::new()
-                }
-                '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '0' => {
-                    cumulated.push(c);
-                }
-                _ => {
-                    //skip
-                }
-            };
-        }
-        map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePatternsError(pub String);
+
+impl Display for ParsePatternsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not a built in pattern", self.0)
+    }
+}
+
+impl FromStr for Patterns {
+    type Err = ParsePatternsError;
+
+    /// finds a pattern by its name, ignoring case, spaces and dashes.
+    fn from_str(name: &str) -> Result<Patterns, ParsePatternsError> {
+        Patterns::ALL
+            .into_iter()
+            .find(|pattern| key(pattern.name()) == key(name))
+            .ok_or_else(|| ParsePatternsError(name.to_string()))
     }
 }
 
@@ -72,6 +97,15 @@ mod test {
         println!("{:?}", Patterns::SnarkLoop.get_life_positions())
     }
     #[test]
+    fn names() {
+        for pattern in Patterns::ALL {
+            assert_eq!(pattern.name().parse(), Ok(pattern));
+        }
+        assert_eq!("gosper-glider-gun".parse(), Ok(Patterns::GosperGliderGun));
+        assert_eq!("SIR ROBIN".parse(), Ok(Patterns::SirRobin));
+        assert!("glider".parse::<Patterns>().is_err());
+    }
+    #[test]
     fn rle_parsing() {
         assert_eq!(
             Patterns::Copperhead.get_life_positions(),
@@ -8,10 +8,33 @@ use std::{cell::RefCell, collections::HashMap, hash::Hash, rc::Rc};
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum PixelState {
     Alive,
+    /// a cell on its way from alive to dead under a Generations rule,
+    /// with its state number starting at 2.
+    Dying(u8),
     #[default]
     Dead,
 }
 
+impl From<u8> for PixelState {
+    fn from(state: u8) -> Self {
+        match state {
+            0 => PixelState::Dead,
+            1 => PixelState::Alive,
+            state => PixelState::Dying(state),
+        }
+    }
+}
+
+impl From<PixelState> for u8 {
+    fn from(state: PixelState) -> Self {
+        match state {
+            PixelState::Dead => 0,
+            PixelState::Alive => 1,
+            PixelState::Dying(state) => state,
+        }
+    }
+}
+
 impl Distribution<PixelState> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PixelState {
         match rng.gen_bool(0.5) {
@@ -84,6 +107,9 @@ where
         let position = neighbour.borrow().get_postion().clone();
         self.neighbours.entry(position).or_insert(neighbour);
     }
+    pub fn clear_neighbours(&mut self) {
+        self.neighbours.clear();
+    }
     pub fn count_alive_neighbours(&self) -> u8 {
         let v = self.neighbours.values();
         v.fold(0, |acc, nb| match nb.borrow().get_state() {
@@ -123,4 +149,12 @@ mod test {
         assert_eq!(pixel.get_postion().get_y(), &8);
         assert_eq!(pixel.get_state(), &PixelState::default());
     }
+
+    #[test]
+    fn state_numbers() {
+        for state in 0..=u8::MAX {
+            assert_eq!(u8::from(PixelState::from(state)), state);
+        }
+        assert_eq!(PixelState::from(3), PixelState::Dying(3));
+    }
 }
@@ -0,0 +1,236 @@
+//! how a pattern is put down: where, turned or mirrored which way, and in which phase.
+
+use std::fmt::Display;
+
+/// the 8 ways to rotate and reflect a pattern, with y pointing down as on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Transform {
+    #[default]
+    Identity,
+    /// a quarter turn clockwise.
+    Rotate90,
+    Rotate180,
+    /// a quarter turn counterclockwise.
+    Rotate270,
+    /// mirrored left to right.
+    FlipHorizontal,
+    /// mirrored top to bottom.
+    FlipVertical,
+    /// mirrored along the diagonal from the top left, swapping x and y.
+    FlipDiagonal,
+    /// mirrored along the diagonal from the top right.
+    FlipAntiDiagonal,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::FlipDiagonal,
+        Transform::FlipAntiDiagonal,
+    ];
+
+    /// moves a cell around the origin.
+    pub fn apply(&self, (x, y): (i64, i64)) -> (i64, i64) {
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (-y, x),
+            Transform::Rotate180 => (-x, -y),
+            Transform::Rotate270 => (y, -x),
+            Transform::FlipHorizontal => (-x, y),
+            Transform::FlipVertical => (x, -y),
+            Transform::FlipDiagonal => (y, x),
+            Transform::FlipAntiDiagonal => (-y, -x),
+        }
+    }
+
+    /// this transform followed by `next`.
+    pub fn then(&self, next: Transform) -> Transform {
+        let moved = |transform: &Transform| [transform.apply((1, 0)), transform.apply((0, 1))];
+        let target = [
+            next.apply(self.apply((1, 0))),
+            next.apply(self.apply((0, 1))),
+        ];
+        *Transform::ALL
+            .iter()
+            .find(|transform| moved(transform) == target)
+            .expect("the transforms are closed under composition")
+    }
+
+    /// the transform that undoes this one.
+    pub fn inverse(&self) -> Transform {
+        *Transform::ALL
+            .iter()
+            .find(|inverse| self.then(**inverse) == Transform::Identity)
+            .expect("every transform can be undone")
+    }
+}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Transform::Identity => "as is",
+            Transform::Rotate90 => "turned clockwise",
+            Transform::Rotate180 => "turned around",
+            Transform::Rotate270 => "turned counterclockwise",
+            Transform::FlipHorizontal => "mirrored left to right",
+            Transform::FlipVertical => "mirrored top to bottom",
+            Transform::FlipDiagonal => "mirrored along the diagonal",
+            Transform::FlipAntiDiagonal => "mirrored along the anti-diagonal",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// a pattern run for `phase` generations, then transformed,
+/// with the top left corner of what is left at `position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Placement {
+    pub position: (i64, i64),
+    pub transform: Transform,
+    pub phase: u64,
+}
+
+impl Placement {
+    pub fn at(position: (i64, i64)) -> Placement {
+        Placement {
+            position,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_transform(self, transform: Transform) -> Placement {
+        Placement { transform, ..self }
+    }
+
+    pub fn with_phase(self, phase: u64) -> Placement {
+        Placement { phase, ..self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Placement, Transform};
+    use crate::algorythms::game_of_life::{
+        engine::LifeEngine,
+        pattern::Pattern,
+        stability::{classify, Classification},
+        universe::Universe,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn transforms() {
+        let images: HashSet<(i64, i64)> = Transform::ALL
+            .iter()
+            .map(|transform| transform.apply((2, 1)))
+            .collect();
+        assert_eq!(images.len(), 8);
+        for transform in Transform::ALL {
+            assert_eq!(transform.then(transform.inverse()), Transform::Identity);
+            for next in Transform::ALL {
+                assert_eq!(
+                    transform.then(next).apply((2, 1)),
+                    next.apply(transform.apply((2, 1)))
+                );
+            }
+        }
+        assert_eq!(Transform::Rotate90.apply((1, 0)), (0, 1));
+        assert_eq!(Transform::Rotate90.inverse(), Transform::Rotate270);
+        assert_eq!(
+            Transform::FlipHorizontal.then(Transform::FlipVertical),
+            Transform::Rotate180
+        );
+
+        let glider: Pattern = "bo$2bo$3o!".parse().unwrap();
+        let turned = glider.transformed(Transform::Rotate90);
+        assert_eq!(
+            turned.positions(),
+            vec![(0, 0), (0, 1), (2, 1), (0, 2), (1, 2)]
+        );
+        assert_eq!(turned.size, (3, 3));
+        let back = (0..3).fold(turned, |pattern, _| {
+            pattern.transformed(Transform::Rotate90)
+        });
+        assert_eq!(back, glider);
+        let wide: Pattern = "3o$o!".parse().unwrap();
+        assert_eq!(wide.transformed(Transform::FlipDiagonal).size, (2, 3));
+    }
+
+    #[test]
+    fn phases() {
+        let glider: Pattern = "bo$2bo$3o!".parse().unwrap();
+        // a glider comes back a cell further down and to the right after 4 generations
+        let advanced = glider.advanced(4).unwrap();
+        assert_eq!(advanced.positions(), glider.positions());
+        assert_eq!(advanced.position, Some((1, 1)));
+        assert_eq!(
+            glider.advanced(2).unwrap().positions(),
+            vec![(2, 0), (0, 1), (2, 1), (1, 2), (2, 2)]
+        );
+        assert!("o!"
+            .parse::<Pattern>()
+            .unwrap()
+            .advanced(1)
+            .unwrap()
+            .cells
+            .is_empty());
+
+        // a pattern without a rule takes its phase under the rule of the engine:
+        // in Brian's Brain two cells give birth to four and start dying
+        let mut brain = Universe::new("B2/S/C3".parse().unwrap()).unwrap();
+        brain
+            .place(&"2o!".parse().unwrap(), Placement::at((0, 0)).with_phase(1))
+            .unwrap();
+        assert_eq!(brain.cells().count(), 6);
+        assert_eq!(brain.population(), 4);
+
+        let placed = glider
+            .arranged(
+                Placement::at((10, -3))
+                    .with_transform(Transform::FlipHorizontal)
+                    .with_phase(4),
+            )
+            .unwrap();
+        assert_eq!(placed.position, Some((10, -3)));
+        assert_eq!(
+            placed.positions(),
+            vec![(1, 0), (0, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        assert_eq!(placed.rule, glider.rule);
+
+        // a B0 rule can only place the pattern as it is
+        let b0: Pattern = "x = 2, y = 1, rule = B03/S23\n2o!".parse().unwrap();
+        assert_eq!(b0.advanced(0).unwrap().positions(), b0.positions());
+        assert_eq!(b0.advanced(1), None);
+        assert_eq!(b0.arranged(Placement::at((0, 0)).with_phase(1)), None);
+    }
+
+    #[test]
+    fn collision() {
+        let glider: Pattern = "bo$2bo$3o!".parse().unwrap();
+        let block: Pattern = "2o$2o!".parse().unwrap();
+        // two gliders flying down towards a block, one from each side
+        let experiment = Pattern::combine(&[
+            block.arranged(Placement::at((10, 10))).unwrap(),
+            glider.arranged(Placement::at((0, 0))).unwrap(),
+            glider
+                .arranged(Placement::at((19, 0)).with_transform(Transform::FlipHorizontal))
+                .unwrap(),
+        ]);
+        assert_eq!(experiment.cells.len(), 14);
+        assert_eq!(experiment.position, Some((0, 0)));
+        assert_eq!(experiment.size, (22, 12));
+
+        let mut universe = Universe::default();
+        universe.place(&experiment, Placement::default());
+        assert_eq!(universe.population(), 14);
+        // all three of them are gone after 30 generations
+        assert_eq!(classify(&mut universe, 1000), Classification::Dead);
+        assert_eq!(universe.generation(), 30);
+    }
+}
@@ -0,0 +1,107 @@
+//! the `.cells` format of the LifeWiki: `!` comment lines followed by rows of `.` and `O`.
+//! see <https://conwaylife.com/wiki/Plaintext>.
+
+use super::pattern::{ParseError, ParseErrorKind, Pattern};
+
+pub fn parse(text: &str) -> Result<Pattern, ParseError> {
+    let (mut name, mut author, mut comments) = (None, None, vec![]);
+    let mut cells = vec![];
+    let mut y = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(text) = comment.strip_prefix("Name:") {
+                name = Some(text.trim().to_string());
+            } else if let Some(text) = comment.strip_prefix("Author:") {
+                author = Some(text.trim().to_string());
+            } else {
+                comments.push(comment.trim().to_string());
+            }
+            continue;
+        }
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '.' => {}
+                // some collections draw live cells as `*`
+                'O' | '*' => cells.push(((x as i64, y), 1)),
+                c => {
+                    return Err(ParseError::new(
+                        index + 1,
+                        x + 1,
+                        ParseErrorKind::UnexpectedCharacter(c),
+                    ))
+                }
+            }
+        }
+        y += 1;
+    }
+    let mut pattern = Pattern {
+        name,
+        author,
+        comments,
+        ..Pattern::default()
+    };
+    // keep leading empty rows and columns, they are part of the drawing
+    pattern.size = Pattern::bounding_box(&cells);
+    pattern.cells = cells;
+    Ok(pattern)
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let mut text = String::new();
+    if let Some(name) = &pattern.name {
+        text += &format!("!Name: {}\n", name);
+    }
+    if let Some(author) = &pattern.author {
+        text += &format!("!Author: {}\n", author);
+    }
+    for comment in &pattern.comments {
+        text += &format!("!{}\n", comment);
+    }
+    let (_, rows) = pattern.draw('.', 'O');
+    for row in rows {
+        text += row.trim_end_matches('.');
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, write};
+    use crate::algorythms::game_of_life::pattern::{ParseError, ParseErrorKind, Pattern};
+
+    #[test]
+    fn round_trips() {
+        let text = "!Name: Glider\n!Author: Richard K. Guy\n!a spaceship\n.O\n..O\nOOO\n";
+        let glider = parse(text).unwrap();
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.comments, vec!["a spaceship"]);
+        assert_eq!(glider.size, (3, 3));
+        assert_eq!(
+            glider.positions(),
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        assert_eq!(write(&glider), text);
+
+        // cells outside of the size given, and left of the origin, are still drawn
+        let wide: Pattern = "x = 1, y = 1\n3o!".parse().unwrap();
+        assert_eq!(write(&wide), "OOO\n");
+        let left = Pattern {
+            cells: vec![((-1, 0), 1), ((1, 0), 1)],
+            ..Pattern::default()
+        };
+        assert_eq!(write(&left), "O.O\n");
+
+        // empty rows in between are kept
+        assert_eq!(parse("O\n\n.*").unwrap().positions(), vec![(0, 0), (1, 2)]);
+        assert_eq!(
+            parse(".O\n.x"),
+            Err(ParseError::new(
+                2,
+                2,
+                ParseErrorKind::UnexpectedCharacter('x')
+            ))
+        );
+    }
+}
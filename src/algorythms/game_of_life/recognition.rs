@@ -0,0 +1,283 @@
+//! finds known objects among the cells of a board, in any phase, turned or mirrored any way.
+
+use super::{
+    engine::LifeEngine,
+    pattern::Pattern,
+    placement::{Placement, Transform},
+    rule::Rule,
+    universe::Universe,
+};
+use std::collections::{HashMap, HashSet};
+
+/// the longest period looked for, guns included.
+const MAX_PERIOD: u64 = 120;
+
+/// an object found on the board. placing the library pattern at `placement`,
+/// under the rule of the board, gives exactly the cells that were found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// where the pattern is in the library.
+    pub index: usize,
+    pub name: Option<String>,
+    pub placement: Placement,
+}
+
+/// one phase of a pattern in one orientation, with the cells around it that have to be empty.
+#[derive(Debug, Clone)]
+struct Variant {
+    index: usize,
+    phase: u64,
+    transform: Transform,
+    cells: Vec<((i64, i64), u8)>,
+    surroundings: Vec<(i64, i64)>,
+}
+
+/// the phases of a pattern under the rule, each with its bounding box at the origin.
+/// a pattern comes back either moved, like a spaceship, or with all its cells in place
+/// and maybe more, like a gun. patterns that do not come back within 120 generations,
+/// or under a rule with `B0`, which the universe can not run, are only recognised as they are.
+fn phases(pattern: &Pattern, rule: Rule) -> Vec<Pattern> {
+    let Some(mut universe) = Universe::new(rule) else {
+        return vec![Pattern::from_cells(pattern.cells.clone())];
+    };
+    universe.place_pattern(&Pattern {
+        position: None,
+        rule: None,
+        ..pattern.clone()
+    });
+    let start: HashSet<((i64, i64), u8)> = universe.cells().collect();
+    let mut phases = vec![Pattern::from_cells(universe.cells().collect())];
+    for _ in 1..=MAX_PERIOD {
+        universe.step();
+        let cells: HashSet<((i64, i64), u8)> = universe.cells().collect();
+        let phase = Pattern::from_cells(cells.iter().copied().collect());
+        if phase.cells == phases[0].cells || start.is_subset(&cells) {
+            return phases;
+        }
+        phases.push(phase);
+    }
+    phases.truncate(1);
+    phases
+}
+
+/// looks for the patterns of a library on boards of one rule. the phases and orientations
+/// of every pattern are worked out once, so one recogniser can look at many boards.
+#[derive(Debug, Clone)]
+pub struct Recognizer {
+    names: Vec<Option<String>>,
+    variants: Vec<Variant>,
+}
+
+impl Recognizer {
+    /// the rule the phases are worked out under, whatever rule the patterns name.
+    pub fn new(library: &[Pattern], rule: Rule) -> Recognizer {
+        let mut variants: Vec<Variant> = vec![];
+        for (index, pattern) in library.iter().enumerate() {
+            let mut seen = HashSet::new();
+            for (phase, cells) in phases(pattern, rule).iter().enumerate() {
+                for transform in Transform::ALL {
+                    let cells = cells.transformed(transform).cells;
+                    // symmetric patterns look the same in several orientations and phases
+                    if cells.is_empty() || !seen.insert(cells.clone()) {
+                        continue;
+                    }
+                    let occupied: HashSet<(i64, i64)> =
+                        cells.iter().map(|(position, _)| *position).collect();
+                    let surroundings: HashSet<(i64, i64)> = occupied
+                        .iter()
+                        .flat_map(|(x, y)| {
+                            (-1..=1).flat_map(move |dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+                        })
+                        .filter(|position| !occupied.contains(position))
+                        .collect();
+                    variants.push(Variant {
+                        index,
+                        phase: phase as u64,
+                        transform,
+                        cells,
+                        surroundings: surroundings.into_iter().collect(),
+                    });
+                }
+            }
+        }
+        Recognizer {
+            names: library.iter().map(|pattern| pattern.name.clone()).collect(),
+            variants,
+        }
+    }
+
+    /// every place where a pattern of the library sits with nothing but dead cells around it,
+    /// from the top left to the bottom right. parts of bigger objects that fit on their own,
+    /// like the blocks at the ends of a gun, are found as well.
+    pub fn find(&self, engine: &dyn LifeEngine) -> Vec<Match> {
+        let cells: HashMap<(i64, i64), u8> = engine.cells().collect();
+        let mut matches = vec![];
+        for variant in &self.variants {
+            // the first cell of a variant is in its top row, so every live cell may be it
+            let ((anchor_x, anchor_y), _) = variant.cells[0];
+            for (x, y) in cells.keys() {
+                let (dx, dy) = (x - anchor_x, y - anchor_y);
+                let found = variant
+                    .cells
+                    .iter()
+                    .all(|((x, y), state)| cells.get(&(x + dx, y + dy)) == Some(state))
+                    && variant
+                        .surroundings
+                        .iter()
+                        .all(|(x, y)| !cells.contains_key(&(x + dx, y + dy)));
+                if found {
+                    matches.push(Match {
+                        index: variant.index,
+                        name: self.names[variant.index].clone(),
+                        placement: Placement::at((dx, dy))
+                            .with_transform(variant.transform)
+                            .with_phase(variant.phase),
+                    });
+                }
+            }
+        }
+        matches.sort_by_key(|found| {
+            let (x, y) = found.placement.position;
+            (y, x, found.index)
+        });
+        matches
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Recognizer;
+    use crate::algorythms::game_of_life::{
+        engine::LifeEngine,
+        pattern::Pattern,
+        patterns::Patterns,
+        placement::{Placement, Transform},
+        rule::Rule,
+        universe::Universe,
+    };
+    use std::collections::HashSet;
+
+    fn library() -> Vec<Pattern> {
+        [
+            ("block", "2o$2o!"),
+            ("blinker", "3o!"),
+            ("glider", "bo$2bo$3o!"),
+            ("beehive", "b2o$o2bo$b2o!"),
+        ]
+        .iter()
+        .map(|(name, rle)| Pattern {
+            name: Some(name.to_string()),
+            ..rle.parse().unwrap()
+        })
+        .chain([Patterns::GosperGliderGun.pattern()])
+        .collect()
+    }
+
+    #[test]
+    fn finds_objects() {
+        let library = library();
+        let mut universe = Universe::default();
+        let placements = [
+            (0, Placement::at((0, 0))),
+            (
+                1,
+                Placement::at((10, 0)).with_transform(Transform::Rotate90),
+            ),
+            (
+                2,
+                Placement::at((20, 0))
+                    .with_transform(Transform::FlipAntiDiagonal)
+                    .with_phase(1),
+            ),
+            (
+                3,
+                Placement::at((0, 10)).with_transform(Transform::Rotate90),
+            ),
+        ];
+        for (index, placement) in placements {
+            universe.place(&library[index], placement);
+        }
+        // a block with a cell next to it is not a block
+        universe.place(&library[0], Placement::at((30, 0)));
+        universe.set_cell((32, 2), 1);
+
+        let matches = Recognizer::new(&library, Rule::conway()).find(&universe);
+        let names: Vec<&str> = matches
+            .iter()
+            .map(|found| found.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, ["block", "blinker", "glider", "beehive"]);
+        for found in &matches {
+            let expected = placements
+                .iter()
+                .find(|(index, _)| *index == found.index)
+                .unwrap()
+                .1;
+            assert_eq!(
+                library[found.index]
+                    .arranged(found.placement)
+                    .unwrap()
+                    .placed_cells()
+                    .collect::<HashSet<_>>(),
+                library[found.index]
+                    .arranged(expected)
+                    .unwrap()
+                    .placed_cells()
+                    .collect::<HashSet<_>>(),
+            );
+        }
+        // the vertical blinker is the horizontal one turned, not its other phase
+        assert_eq!(
+            matches[1].placement,
+            Placement::at((10, 0)).with_transform(Transform::Rotate90)
+        );
+    }
+
+    #[test]
+    fn finds_guns_in_any_phase() {
+        let library = library();
+        let mut universe = Universe::default();
+        universe.place(
+            &library[4],
+            Placement::at((5, 5)).with_transform(Transform::FlipVertical),
+        );
+        universe.step_n(75);
+        let matches = Recognizer::new(&library, Rule::conway()).find(&universe);
+        let gun = matches.iter().find(|found| found.index == 4).unwrap();
+        assert_eq!(gun.placement.phase, 15);
+        assert_eq!(gun.placement.transform, Transform::FlipVertical);
+        // a glider leaves the gun every 30 generations
+        let count = |index| matches.iter().filter(|found| found.index == index).count();
+        assert_eq!(count(2), 3);
+        // the blocks at both ends of the gun have nothing else around them
+        assert_eq!(count(0), 2);
+        assert!(Recognizer::new(&library, Rule::conway())
+            .find(&Universe::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn uses_the_rule_of_the_board() {
+        // two live cells pulling two dying ones along, a spaceship in Brian's Brain
+        let ship: Pattern = "x = 2, y = 2\n2A$2B!".parse().unwrap();
+        let brain: Rule = "B2/S/C3".parse().unwrap();
+        let mut universe = Universe::new(brain).unwrap();
+        universe.place(
+            &ship,
+            Placement::at((3, 3)).with_transform(Transform::Rotate90),
+        );
+        universe.step_n(10);
+        let library = [ship];
+        let matches = Recognizer::new(&library, brain).find(&universe);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|found| found.placement)
+                .collect::<Vec<_>>(),
+            [Placement::at((13, 3)).with_transform(Transform::Rotate90)]
+        );
+        assert!(Recognizer::new(&library, Rule::conway())
+            .find(&universe)
+            .is_empty());
+    }
+}
@@ -0,0 +1,381 @@
+//! the run length encoded format used by Golly and the LifeWiki.
+//! see <https://conwaylife.com/wiki/Run_Length_Encoded>.
+
+use super::pattern::{ParseError, ParseErrorKind, Pattern};
+
+/// reads the state a tag stands for, taking the prefix of multi-state tags
+/// (`pA` to `yO`) from `prefix`.
+fn state(prefix: Option<char>, tag: char) -> Option<u8> {
+    let base = match prefix {
+        Some(prefix @ 'p'..='y') => 24 * (prefix as u32 - 'p' as u32 + 1),
+        Some(_) => return None,
+        None => 0,
+    };
+    match tag {
+        'A'..='X' => u8::try_from(base + tag as u32 - 'A' as u32 + 1).ok(),
+        _ => None,
+    }
+}
+
+/// reads `x = m, y = n, rule = abc` into the size and the rule.
+/// the rule comes last and may contain commas itself, as in `B3/S23:T100,50`.
+fn parse_header(header: &str) -> Option<((usize, usize), Option<String>)> {
+    let (size, rule) = match header.find("rule") {
+        Some(index) => {
+            let (_, rule) = header[index..].split_once('=')?;
+            (&header[..index], Some(rule.trim().to_string()))
+        }
+        None => (header, None),
+    };
+    let (mut width, mut height) = (None, None);
+    for field in size.trim().trim_end_matches(',').split(',') {
+        let (key, value) = field.split_once('=')?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = Some(value.parse().ok()?),
+            "y" => height = Some(value.parse().ok()?),
+            _ => return None,
+        }
+    }
+    Some(((width?, height?), rule))
+}
+
+/// parses an RLE file. the header is optional, which makes the bare cell strings
+/// found around the web readable too; the size then comes from the cells.
+pub fn parse(text: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut header = None;
+    let mut lines = text.lines().enumerate().peekable();
+
+    // comments and the header come first
+    while let Some((index, line)) = lines.peek() {
+        let trimmed = line.trim();
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            let mut chars = comment.chars();
+            let tag = chars.next();
+            let text = chars.as_str().trim().to_string();
+            match tag {
+                Some('N') => pattern.name = Some(text),
+                Some('O') => pattern.author = Some(text),
+                Some('P' | 'R') => {
+                    let position = text
+                        .split_whitespace()
+                        .map(str::parse)
+                        .collect::<Result<Vec<i64>, _>>();
+                    match position.as_deref() {
+                        Ok([x, y]) => pattern.position = Some((*x, *y)),
+                        _ => pattern.comments.push(text),
+                    }
+                }
+                _ => pattern.comments.push(text),
+            }
+        } else if trimmed.starts_with('x') {
+            let (size, rule) = parse_header(trimmed).ok_or_else(|| {
+                ParseError::new(
+                    index + 1,
+                    1,
+                    ParseErrorKind::InvalidHeader(trimmed.to_string()),
+                )
+            })?;
+            header = Some(size);
+            pattern.rule = rule;
+            lines.next();
+            break;
+        } else if !trimmed.is_empty() {
+            break;
+        }
+        lines.next();
+    }
+
+    let (mut x, mut y) = (0i64, 0i64);
+    let mut count = String::new();
+    let mut prefix = None;
+    let mut terminated = false;
+    'lines: for (index, line) in lines {
+        for (column, c) in line.chars().enumerate() {
+            let error = |kind| ParseError::new(index + 1, column + 1, kind);
+            if let Some(prefix) = prefix.filter(|_| !('A'..='X').contains(&c)) {
+                let tag = format!("{}{}", prefix, c);
+                return Err(error(ParseErrorKind::InvalidState(tag)));
+            }
+            let run = || {
+                if count.is_empty() {
+                    Ok(1)
+                } else {
+                    match count.parse::<i64>() {
+                        Ok(run) if run > 0 => Ok(run),
+                        _ => Err(error(ParseErrorKind::InvalidCount(count.clone()))),
+                    }
+                }
+            };
+            match c {
+                '0'..='9' => {
+                    count.push(c);
+                    continue;
+                }
+                'b' | '.' => x += run()?,
+                'o' | 'A'..='X' => {
+                    let state = match c {
+                        'o' => 1,
+                        tag => {
+                            let prefix = prefix.take();
+                            state(prefix, tag).ok_or_else(|| {
+                                let tag = prefix.into_iter().chain([tag]).collect();
+                                error(ParseErrorKind::InvalidState(tag))
+                            })?
+                        }
+                    };
+                    for _ in 0..run()? {
+                        pattern.cells.push(((x, y), state));
+                        x += 1;
+                    }
+                }
+                'p'..='y' => {
+                    // the run count belongs to the tag after the prefix
+                    prefix = Some(c);
+                    continue;
+                }
+                '$' => {
+                    y += run()?;
+                    x = 0;
+                }
+                '!' => {
+                    if !count.is_empty() {
+                        return Err(error(ParseErrorKind::InvalidCount(count)));
+                    }
+                    terminated = true;
+                    break 'lines;
+                }
+                c if c.is_whitespace() => {
+                    if count.is_empty() {
+                        continue;
+                    }
+                    return Err(error(ParseErrorKind::InvalidCount(count)));
+                }
+                c => return Err(error(ParseErrorKind::UnexpectedCharacter(c))),
+            }
+            count.clear();
+        }
+    }
+    if !terminated {
+        let line = text.lines().count();
+        let column = text.lines().last().map_or(0, |line| line.chars().count());
+        return Err(ParseError::new(line, column, ParseErrorKind::UnexpectedEnd));
+    }
+
+    pattern.size = header.unwrap_or_else(|| Pattern::bounding_box(&pattern.cells));
+    Ok(pattern)
+}
+
+/// lines of the cell data are wrapped before they get longer than this, as the format asks.
+const LINE_LENGTH: usize = 70;
+
+/// the tag of a cell state, `b`/`o` for two-state patterns and `.`, `A` to `X` or `pA` to `yO` otherwise.
+fn tag(state: u8, multi_state: bool) -> String {
+    match (state, multi_state) {
+        (0, false) => "b".to_string(),
+        (_, false) => "o".to_string(),
+        (0, true) => ".".to_string(),
+        (state, true) => {
+            let letter = (b'A' + (state - 1) % 24) as char;
+            match (state - 1) / 24 {
+                0 => letter.to_string(),
+                prefix => format!("{}{}", (b'p' + prefix - 1) as char, letter),
+            }
+        }
+    }
+}
+
+/// writes a pattern as RLE, with its name, author and comments as `#` lines and the rule,
+/// if it has one, in the header.
+pub fn write(pattern: &Pattern) -> String {
+    let mut text = String::new();
+    if let Some(name) = &pattern.name {
+        text += &format!("#N {}\n", name);
+    }
+    if let Some(author) = &pattern.author {
+        text += &format!("#O {}\n", author);
+    }
+    for comment in &pattern.comments {
+        text += &format!("#C {}\n", comment);
+    }
+    if let Some((x, y)) = pattern.position {
+        text += &format!("#R {} {}\n", x, y);
+    }
+    text += &format!("x = {}, y = {}", pattern.size.0, pattern.size.1);
+    if let Some(rule) = &pattern.rule {
+        text += &format!(", rule = {}", rule);
+    }
+    text.push('\n');
+
+    let multi_state = pattern.cells.iter().any(|(_, state)| *state > 1);
+    let mut cells = pattern.cells.clone();
+    cells.sort_by_key(|((x, y), _)| (*y, *x));
+
+    // runs of (count, tag), without the dead cells at the end of a row
+    let mut runs: Vec<(i64, String)> = vec![];
+    let mut push = |count: i64, tag: String| match runs.last_mut() {
+        Some((last_count, last_tag)) if *last_tag == tag => *last_count += count,
+        _ => runs.push((count, tag)),
+    };
+    let (mut x, mut y) = (0, 0);
+    for ((cell_x, cell_y), state) in cells {
+        if cell_y > y {
+            push(cell_y - y, "$".to_string());
+            (x, y) = (0, cell_y);
+        }
+        if cell_x > x {
+            push(cell_x - x, tag(0, multi_state));
+        }
+        push(1, tag(state, multi_state));
+        x = cell_x + 1;
+    }
+    push(1, "!".to_string());
+
+    let mut line = String::new();
+    for (count, tag) in runs {
+        let run = match count {
+            1 => tag,
+            count => format!("{}{}", count, tag),
+        };
+        if line.len() + run.len() > LINE_LENGTH {
+            text += &line;
+            text.push('\n');
+            line.clear();
+        }
+        line += &run;
+    }
+    text + &line + "\n"
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, write};
+    use crate::algorythms::game_of_life::pattern::{ParseError, ParseErrorKind, Pattern};
+
+    const GLIDER: &str = "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+#C www.conwaylife.com/wiki/index.php?title=Glider
+x = 3, y = 3, rule = B3/S23
+bob$2bo$3o!";
+
+    #[test]
+    fn glider() {
+        let glider = parse(GLIDER).unwrap();
+        assert_eq!(glider.name.as_deref(), Some("Glider"));
+        assert_eq!(glider.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(glider.comments.len(), 2);
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(glider.size, (3, 3));
+        assert_eq!(
+            glider.positions(),
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        assert_eq!(GLIDER.parse::<Pattern>(), Ok(glider.clone()));
+        assert_eq!(Pattern::from_reader(GLIDER.as_bytes()), Ok(glider));
+
+        // a tag that is not a single byte
+        let pattern = parse("#é comment\n#Nüll\nbo!").unwrap();
+        assert_eq!(pattern.comments, ["comment"]);
+        assert_eq!(pattern.name.as_deref(), Some("üll"));
+    }
+
+    #[test]
+    fn without_header() {
+        let pattern = parse("#P -1 -2\n  2o2$\n b2o\n!and anything after").unwrap();
+        assert_eq!(pattern.position, Some((-1, -2)));
+        assert_eq!(pattern.positions(), vec![(0, 0), (1, 0), (1, 2), (2, 2)]);
+        assert_eq!(pattern.size, (3, 3));
+        assert_eq!(pattern.rule, None);
+    }
+
+    #[test]
+    fn multiple_states() {
+        let pattern = parse("x = 4, y = 2, rule = Generations\n.A2B$pAyOX!").unwrap();
+        assert_eq!(
+            pattern.cells,
+            vec![
+                ((1, 0), 1),
+                ((2, 0), 2),
+                ((3, 0), 2),
+                ((0, 1), 25),
+                ((1, 1), 255),
+                ((2, 1), 24)
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        let error = |text: &str| parse(text).unwrap_err();
+        assert_eq!(
+            error("x = 3, y = 3\nbo$2bo$3z!"),
+            ParseError::new(2, 9, ParseErrorKind::UnexpectedCharacter('z'))
+        );
+        assert_eq!(error("x = 3 y = 3\nbo!").line, 1);
+        assert!(matches!(
+            error("x = 3, y\nbo!").kind,
+            ParseErrorKind::InvalidHeader(_)
+        ));
+        assert_eq!(
+            error("3o$99999999999999999999o!").kind,
+            ParseErrorKind::InvalidCount("99999999999999999999".to_string())
+        );
+        assert_eq!(
+            error("2 o!").kind,
+            ParseErrorKind::InvalidCount("2".to_string())
+        );
+        assert_eq!(
+            error("pZ!").kind,
+            ParseErrorKind::InvalidState("pZ".to_string())
+        );
+        assert_eq!(
+            error("po!").kind,
+            ParseErrorKind::InvalidState("po".to_string())
+        );
+        assert_eq!(
+            error("yP!").kind,
+            ParseErrorKind::InvalidState("yP".to_string())
+        );
+        assert_eq!(
+            error("x = 2, y = 1\n2o"),
+            ParseError::new(2, 2, ParseErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error("3o3!").to_string(),
+            "line 1, column 4: invalid run count 3"
+        );
+    }
+
+    #[test]
+    fn writes() {
+        let glider = parse(GLIDER).unwrap();
+        assert_eq!(
+            write(&glider),
+            "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+#C www.conwaylife.com/wiki/index.php?title=Glider
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+"
+        );
+        assert_eq!(parse(&write(&glider)), Ok(glider));
+
+        let states = parse("x = 4, y = 3\n.A2B2$pAyOX!").unwrap();
+        assert_eq!(write(&states), "x = 4, y = 3\n.A2B2$pAyOX!\n");
+        assert_eq!(write(&Pattern::default()), "x = 0, y = 0\n!\n");
+    }
+
+    #[test]
+    fn wraps_lines() {
+        // every other cell alive makes runs that cannot be merged
+        let cells = (0..200).map(|x| ((x * 2, x % 3), 1)).collect();
+        let pattern = Pattern::from_cells(cells);
+        let text = write(&pattern);
+        assert!(text.lines().all(|line| line.len() <= 70));
+        assert!(text.lines().count() > 5);
+        assert_eq!(parse(&text).unwrap().cells, pattern.cells);
+    }
+}
@@ -0,0 +1,270 @@
+use super::topology::Grid;
+use std::{fmt::Display, str::FromStr};
+
+/// a Life-like rule: the neighbour counts at which a dead cell is born and a live one survives.
+/// Generations rules have more than two states, a live cell that does not survive
+/// passes through the dying states `2..states` before it is dead and can be born again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+    states: u8,
+    /// the bounded grid given after a `:`, as in `B3/S23:T100,50`.
+    grid: Option<Grid>,
+}
+
+impl Rule {
+    pub fn new(birth: &[u8], survival: &[u8]) -> Option<Rule> {
+        let mut rule = Rule {
+            birth: [false; 9],
+            survival: [false; 9],
+            states: 2,
+            grid: None,
+        };
+        for (counts, table) in [(birth, &mut rule.birth), (survival, &mut rule.survival)] {
+            for count in counts {
+                *table.get_mut(*count as usize)? = true;
+            }
+        }
+        Some(rule)
+    }
+
+    /// a Generations rule with `states` states, counting dead and alive.
+    pub fn generations(birth: &[u8], survival: &[u8], states: u8) -> Option<Rule> {
+        let rule = Rule::new(birth, survival)?;
+        (states >= 2).then_some(Rule { states, ..rule })
+    }
+
+    /// Conway's Game of Life, B3/S23.
+    pub fn conway() -> Rule {
+        Rule::new(&[3], &[2, 3]).unwrap()
+    }
+
+    pub fn births(&self, neighbours: u8) -> bool {
+        self.birth.get(neighbours as usize) == Some(&true)
+    }
+
+    pub fn survives(&self, neighbours: u8) -> bool {
+        self.survival.get(neighbours as usize) == Some(&true)
+    }
+
+    /// whether a cell is alive in the next generation.
+    pub fn next_state(&self, alive: bool, neighbours: u8) -> bool {
+        if alive {
+            self.survives(neighbours)
+        } else {
+            self.births(neighbours)
+        }
+    }
+
+    pub fn with_grid(self, grid: Option<Grid>) -> Rule {
+        Rule { grid, ..self }
+    }
+
+    pub fn grid(&self) -> Option<&Grid> {
+        self.grid.as_ref()
+    }
+
+    /// the number of states, 2 for Life-like rules.
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// the state of a cell in the next generation, 0 being dead and 1 alive.
+    /// only live cells count as neighbours.
+    pub fn next(&self, state: u8, neighbours: u8) -> u8 {
+        match state {
+            0 => self.births(neighbours) as u8,
+            1 if self.survives(neighbours) => 1,
+            // dying cells age until they wrap around to dead, as do states the rule does not have
+            state => state
+                .checked_add(1)
+                .filter(|next| *next < self.states)
+                .unwrap_or(0),
+        }
+    }
+
+    fn counts(table: &[bool; 9]) -> String {
+        (0..9)
+            .filter(|count| table[*count])
+            .map(|count| count.to_string())
+            .collect()
+    }
+
+    /// reads the digits of one half of a rule, without repeats.
+    fn parse_counts(digits: &str) -> Option<Vec<u8>> {
+        let counts = digits
+            .chars()
+            .map(|digit| {
+                digit
+                    .to_digit(10)
+                    .filter(|count| *count < 9)
+                    .map(|count| count as u8)
+            })
+            .collect::<Option<Vec<u8>>>()?;
+        let mut distinct = counts.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        (distinct.len() == counts.len()).then_some(counts)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::conway()
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "B{}/S{}",
+            Rule::counts(&self.birth),
+            Rule::counts(&self.survival)
+        )?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        if let Some(grid) = &self.grid {
+            write!(f, ":{}", grid)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRuleError(pub String);
+
+impl Display for ParseRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not a rule in B/S notation", self.0)
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    /// reads `B36/S23`, the halves in either order, with or without the slash,
+    /// or the older `23/36` form which gives the survival counts first.
+    /// Generations rules add the number of states, as in `B2/S/C3` or `345/2/4`.
+    fn from_str(text: &str) -> Result<Rule, ParseRuleError> {
+        let error = || ParseRuleError(text.to_string());
+        if let Some((rule, grid)) = text.split_once(':') {
+            let grid = grid.parse().map_err(|_| error())?;
+            return Ok(rule.parse::<Rule>()?.with_grid(Some(grid)));
+        }
+        let rule = text.trim().to_ascii_uppercase();
+        if let [first, second, states] = rule.split('/').collect::<Vec<&str>>().as_slice() {
+            let states = states
+                .trim_start_matches(['C', 'G'])
+                .parse()
+                .map_err(|_| error())?;
+            let rule = format!("{}/{}", first, second).parse::<Rule>()?;
+            return (states >= 2)
+                .then_some(Rule { states, ..rule })
+                .ok_or_else(error);
+        }
+        let (birth, survival) = if rule.starts_with(['B', 'S']) {
+            // split in front of the second letter, so `B3S23` and `B3/S23` both work
+            let second = rule[1..]
+                .find(['B', 'S'])
+                .map(|index| index + 1)
+                .ok_or_else(error)?;
+            let (first, second) = rule.split_at(second);
+            let first = first.trim_end_matches('/');
+            match (first.split_at(1), second.split_at(1)) {
+                (("B", birth), ("S", survival)) | (("S", survival), ("B", birth)) => {
+                    (birth, survival)
+                }
+                _ => return Err(error()),
+            }
+        } else {
+            let (survival, birth) = rule.split_once('/').ok_or_else(error)?;
+            (birth, survival)
+        };
+        let birth = Rule::parse_counts(birth).ok_or_else(error)?;
+        let survival = Rule::parse_counts(survival).ok_or_else(error)?;
+        Rule::new(&birth, &survival).ok_or_else(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Rule;
+
+    #[test]
+    fn parses_notations() {
+        let rule = |text: &str| text.parse::<Rule>().map(|rule| rule.to_string());
+        assert_eq!(rule("B3/S23"), Ok("B3/S23".to_string()));
+        assert_eq!(rule("b36/s23"), Ok("B36/S23".to_string()));
+        assert_eq!(rule("B2/S"), Ok("B2/S".to_string()));
+        assert_eq!(rule("B3678/S34678"), Ok("B3678/S34678".to_string()));
+        assert_eq!(rule("B3S23"), Ok("B3/S23".to_string()));
+        assert_eq!(rule("S23/B3"), Ok("B3/S23".to_string()));
+        assert_eq!(rule("23/3"), Ok("B3/S23".to_string()));
+        assert_eq!(rule("/2"), Ok("B2/S".to_string()));
+        assert_eq!(rule("B/S012345678"), Ok("B/S012345678".to_string()));
+
+        assert!(rule("B9/S23").is_err());
+        assert!(rule("B33/S23").is_err());
+        assert!(rule("B3/B23").is_err());
+        assert!(rule("B3").is_err());
+        assert!(rule("Generations").is_err());
+        assert_eq!(Rule::default(), Rule::conway());
+        assert_eq!(Rule::conway().states(), 2);
+    }
+
+    #[test]
+    fn generations() {
+        let rule = |text: &str| text.parse::<Rule>().map(|rule| rule.to_string());
+        assert_eq!(rule("B2/S/C3"), Ok("B2/S/C3".to_string()));
+        assert_eq!(rule("345/2/4"), Ok("B2/S345/C4".to_string()));
+        assert_eq!(rule("b2/s/g3"), Ok("B2/S/C3".to_string()));
+        assert_eq!(rule("B3/S23/C2"), Ok("B3/S23".to_string()));
+        assert!(rule("B2/S/C1").is_err());
+        assert!(rule("B2/S/C").is_err());
+        assert!(rule("B2/S/C3/4").is_err());
+
+        let brians_brain: Rule = "B2/S/C3".parse().unwrap();
+        assert_eq!(brians_brain.next(0, 2), 1);
+        assert_eq!(brians_brain.next(0, 3), 0);
+        assert_eq!(brians_brain.next(1, 2), 2);
+        assert_eq!(brians_brain.next(2, 2), 0);
+
+        let star_wars: Rule = "345/2/4".parse().unwrap();
+        assert_eq!(star_wars.next(1, 4), 1);
+        assert_eq!(star_wars.next(1, 2), 2);
+        assert_eq!(star_wars.next(2, 4), 3);
+        assert_eq!(star_wars.next(3, 4), 0);
+        assert_eq!(Rule::conway().next(1, 1), 0);
+        assert_eq!(Rule::conway().next(2, 0), 0);
+        assert_eq!(star_wars.next(255, 3), 0);
+    }
+
+    #[test]
+    fn bounded_grids() {
+        let rule: Rule = "B3/S23:T100,50".parse().unwrap();
+        assert_eq!(rule.grid().map(|grid| grid.width), Some(100));
+        assert_eq!(rule.to_string(), "B3/S23:T100,50");
+        assert_eq!(
+            "B2/S/C3:K10*,8"
+                .parse::<Rule>()
+                .map(|rule| rule.to_string()),
+            Ok("B2/S/C3:K10*,8".to_string())
+        );
+        assert!("B3/S23:Q1".parse::<Rule>().is_err());
+        assert!("B3/S23:T1:T1".parse::<Rule>().is_err());
+        assert!("B3/S23:é4".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn next_state() {
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert!(highlife.next_state(false, 6));
+        assert!(!Rule::conway().next_state(false, 6));
+        assert!(highlife.next_state(true, 2));
+        assert!(!highlife.next_state(false, 2));
+        assert!(!highlife.next_state(true, 4));
+    }
+}
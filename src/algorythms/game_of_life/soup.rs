@@ -0,0 +1,293 @@
+use super::pattern::Pattern;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{collections::BTreeSet, fmt::Display, str::FromStr};
+
+/// how a soup is mirrored or rotated around its centre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Symmetry {
+    /// no symmetry at all.
+    #[default]
+    C1,
+    /// the same after half a turn.
+    C2,
+    /// the same after a quarter turn.
+    C4,
+    /// mirrored left to right.
+    D2,
+    /// mirrored left to right and top to bottom.
+    D4,
+    /// mirrored along both axes and both diagonals.
+    D8,
+}
+
+impl Symmetry {
+    /// whether the symmetry turns or mirrors along a diagonal, which only fits a square.
+    fn needs_square(&self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+
+    /// the cells a cell is copied to in a `width` by `height` area, itself included.
+    fn images(&self, (x, y): (i64, i64), (width, height): (i64, i64)) -> Vec<(i64, i64)> {
+        let (right, bottom) = (width - 1, height - 1);
+        let half_turn = (right - x, bottom - y);
+        let quarter_turns = [(right - y, x), (y, bottom - x)];
+        match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), half_turn],
+            Symmetry::C4 => vec![(x, y), quarter_turns[0], half_turn, quarter_turns[1]],
+            Symmetry::D2 => vec![(x, y), (right - x, y)],
+            Symmetry::D4 => vec![(x, y), (right - x, y), (x, bottom - y), half_turn],
+            Symmetry::D8 => vec![
+                (x, y),
+                (right - x, y),
+                (x, bottom - y),
+                half_turn,
+                (y, x),
+                quarter_turns[0],
+                quarter_turns[1],
+                (right - y, bottom - x),
+            ],
+        }
+    }
+
+    /// the size of a soup grown from a `width` by `height` region: the region is doubled
+    /// along every axis the symmetry copies it across. C4 and D8 only use the largest
+    /// square of the region.
+    pub fn size(&self, (width, height): (usize, usize)) -> (usize, usize) {
+        match self {
+            Symmetry::C1 => (width, height),
+            Symmetry::C2 => (width, height * 2),
+            Symmetry::D2 => (width * 2, height),
+            Symmetry::D4 => (width * 2, height * 2),
+            Symmetry::C4 | Symmetry::D8 => (width.min(height) * 2, width.min(height) * 2),
+        }
+    }
+
+    /// the name apgsearch and Catagolue give the symmetry of a soup of this size. the centre
+    /// of an odd side lies in a row or column of cells, that of an even one between two,
+    /// which the number after the `_` tells apart.
+    pub fn catagolue_name(&self, (width, height): (usize, usize)) -> &'static str {
+        let (odd_width, odd_height) = (width % 2 == 1, height % 2 == 1);
+        match (self, odd_width, odd_height) {
+            (Symmetry::C1, _, _) => "C1",
+            (Symmetry::C2, true, true) => "C2_1",
+            (Symmetry::C2, false, false) => "C2_4",
+            (Symmetry::C2, _, _) => "C2_2",
+            (Symmetry::C4, true, _) => "C4_1",
+            (Symmetry::C4, false, _) => "C4_4",
+            (Symmetry::D2, true, _) => "D2_+1",
+            (Symmetry::D2, false, _) => "D2_+2",
+            (Symmetry::D4, true, true) => "D4_+1",
+            (Symmetry::D4, false, false) => "D4_+4",
+            (Symmetry::D4, _, _) => "D4_+2",
+            (Symmetry::D8, true, _) => "D8_1",
+            (Symmetry::D8, false, _) => "D8_4",
+        }
+    }
+}
+
+impl Display for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSymmetryError(pub String);
+
+impl Display for ParseSymmetryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not one of C1, C2, C4, D2, D4 or D8", self.0)
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = ParseSymmetryError;
+
+    fn from_str(text: &str) -> Result<Symmetry, ParseSymmetryError> {
+        match text.to_uppercase().as_str() {
+            "C1" => Ok(Symmetry::C1),
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D2" => Ok(Symmetry::D2),
+            "D4" => Ok(Symmetry::D4),
+            "D8" => Ok(Symmetry::D8),
+            _ => Err(ParseSymmetryError(text.to_string())),
+        }
+    }
+}
+
+/// random live cells filling a `width` by `height` area, the same every time for the same seed.
+/// every cell draws once for itself and all the cells the symmetry copies it to,
+/// so the density holds for every symmetry. C4 and D8 only fill the largest square.
+pub fn symmetric_cells(
+    seed: u64,
+    (width, height): (usize, usize),
+    density: f64,
+    symmetry: Symmetry,
+) -> Vec<(i64, i64)> {
+    let (width, height) = match symmetry.needs_square() {
+        true => (width.min(height), width.min(height)),
+        false => (width, height),
+    };
+    let size = (width as i64, height as i64);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut cells = BTreeSet::new();
+    for y in 0..size.1 {
+        for x in 0..size.0 {
+            let images = symmetry.images((x, y), size);
+            // only the first cell of every group draws
+            if images.iter().min() == Some(&(x, y)) && rng.gen_bool(density.clamp(0.0, 1.0)) {
+                cells.extend(images);
+            }
+        }
+    }
+    cells.into_iter().collect()
+}
+
+/// a random start, the same every time for the same seed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Soup {
+    pub seed: u64,
+    /// the size of the random region, before the symmetry copies it.
+    pub width: usize,
+    pub height: usize,
+    /// the chance of every cell to start alive.
+    pub density: f64,
+    pub symmetry: Symmetry,
+}
+
+impl Soup {
+    /// a 16 by 16 soup with half of the cells alive, as Catagolue uses them.
+    pub fn new(seed: u64) -> Soup {
+        Soup {
+            seed,
+            width: 16,
+            height: 16,
+            density: 0.5,
+            symmetry: Symmetry::C1,
+        }
+    }
+
+    /// the width and height of the whole soup.
+    pub fn size(&self) -> (usize, usize) {
+        self.symmetry.size((self.width, self.height))
+    }
+
+    /// the symmetry as apgsearch names it, see [`Symmetry::catagolue_name`].
+    pub fn catagolue_symmetry(&self) -> &'static str {
+        self.symmetry.catagolue_name(self.size())
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        let cells = symmetric_cells(self.seed, self.size(), self.density, self.symmetry);
+        Pattern::placed(cells.into_iter().map(|cell| (cell, 1)).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Soup, Symmetry};
+    use std::collections::HashSet;
+
+    #[test]
+    fn repeats_with_seed() {
+        let soup = Soup::new(42);
+        assert_eq!(soup.pattern(), soup.pattern());
+        assert_ne!(soup.pattern(), Soup::new(43).pattern());
+        let cells = soup.pattern().cells.len();
+        assert!((64..192).contains(&cells));
+
+        let sparse = Soup {
+            density: 0.0,
+            ..soup
+        };
+        assert!(sparse.pattern().cells.is_empty());
+        let full = Soup {
+            width: 3,
+            height: 2,
+            density: 1.0,
+            ..soup
+        };
+        assert_eq!(full.pattern().cells.len(), 6);
+    }
+
+    #[test]
+    fn symmetries() {
+        let turn = |(x, y): (i64, i64), size: i64| (size - 1 - y, x);
+        let mirror = |(x, y): (i64, i64), width: i64| (width - 1 - x, y);
+        let flip = |(x, y): (i64, i64), height: i64| (x, height - 1 - y);
+        for symmetry in [
+            Symmetry::C1,
+            Symmetry::C2,
+            Symmetry::C4,
+            Symmetry::D2,
+            Symmetry::D4,
+            Symmetry::D8,
+        ] {
+            let soup = Soup {
+                width: 8,
+                height: 6,
+                symmetry,
+                ..Soup::new(1)
+            };
+            let (width, height) = soup.size();
+            let pattern = soup.pattern();
+            let cells: HashSet<(i64, i64)> = pattern.placed_cells().map(|(cell, _)| cell).collect();
+            let (width, height) = (width as i64, height as i64);
+            let same = |map: &dyn Fn((i64, i64)) -> (i64, i64)| {
+                cells.iter().map(|cell| map(*cell)).collect::<HashSet<_>>() == cells
+            };
+            let half_turn = same(&|(x, y)| (width - 1 - x, height - 1 - y));
+            let quarter_turn = same(&|cell| turn(cell, width));
+            let mirrored = same(&|cell| mirror(cell, width));
+            let flipped = same(&|cell| flip(cell, height));
+            let diagonal = same(&|(x, y)| (y, x));
+            assert_eq!(
+                (half_turn, quarter_turn, mirrored, flipped, diagonal),
+                match symmetry {
+                    Symmetry::C1 => (false, false, false, false, false),
+                    Symmetry::C2 => (true, false, false, false, false),
+                    Symmetry::C4 => (true, true, false, false, false),
+                    Symmetry::D2 => (false, false, true, false, false),
+                    Symmetry::D4 => (true, false, true, true, false),
+                    Symmetry::D8 => (true, true, true, true, true),
+                },
+                "{}",
+                symmetry
+            );
+            assert_eq!(symmetry.to_string().parse(), Ok(symmetry));
+        }
+        assert_eq!(Symmetry::D8.size((8, 6)), (12, 12));
+        assert_eq!(Symmetry::C4.size((4, 8)), (8, 8));
+        assert_eq!(Symmetry::C2.size((8, 6)), (8, 12));
+        assert_eq!("d4".parse(), Ok(Symmetry::D4));
+
+        // the soups grown here always have an even side where they are mirrored or turned
+        let names = [
+            (Symmetry::C1, "C1"),
+            (Symmetry::C2, "C2_4"),
+            (Symmetry::C4, "C4_4"),
+            (Symmetry::D2, "D2_+2"),
+            (Symmetry::D4, "D4_+4"),
+            (Symmetry::D8, "D8_4"),
+        ];
+        for (symmetry, name) in names {
+            let soup = Soup {
+                symmetry,
+                ..Soup::new(1)
+            };
+            assert_eq!(soup.catagolue_symmetry(), name);
+        }
+        let odd = Soup {
+            width: 15,
+            symmetry: Symmetry::C2,
+            ..Soup::new(1)
+        };
+        assert_eq!(odd.catagolue_symmetry(), "C2_2");
+        assert_eq!(Symmetry::C2.catagolue_name((15, 15)), "C2_1");
+        assert_eq!(Symmetry::D4.catagolue_name((15, 15)), "D4_+1");
+        assert_eq!(Symmetry::D8.catagolue_name((15, 15)), "D8_1");
+        assert!("D3".parse::<Symmetry>().is_err());
+    }
+}
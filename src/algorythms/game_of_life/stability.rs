@@ -0,0 +1,241 @@
+//! tells when a pattern has settled down by remembering the recent generations
+//! and waiting for one to come back, possibly somewhere else on the board.
+
+use super::engine::LifeEngine;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+};
+
+/// the longest period a [`Detector`] looks for, unless it is given another one.
+pub const MAX_PERIOD: u64 = 1000;
+
+/// what a pattern turned out to be once it started repeating itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Classification {
+    /// every cell died.
+    Dead,
+    /// nothing changes from one generation to the next.
+    StillLife,
+    /// comes back in the same place after `period` generations.
+    Oscillator { period: u64 },
+    /// comes back moved by `displacement` after `period` generations.
+    Spaceship {
+        period: u64,
+        displacement: (i64, i64),
+    },
+    /// did not repeat in the generations that were looked at.
+    Unknown,
+}
+
+impl Classification {
+    /// the number of generations after which the pattern repeats, 1 for still lifes and dead boards.
+    pub fn period(&self) -> Option<u64> {
+        match self {
+            Classification::Dead | Classification::StillLife => Some(1),
+            Classification::Oscillator { period } | Classification::Spaceship { period, .. } => {
+                Some(*period)
+            }
+            Classification::Unknown => None,
+        }
+    }
+
+    /// how far the pattern moves in one period.
+    pub fn displacement(&self) -> Option<(i64, i64)> {
+        match self {
+            Classification::Spaceship { displacement, .. } => Some(*displacement),
+            Classification::Unknown => None,
+            _ => Some((0, 0)),
+        }
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self != &Classification::Unknown
+    }
+}
+
+impl Display for Classification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Classification::Dead => write!(f, "dead"),
+            Classification::StillLife => write!(f, "still life"),
+            Classification::Oscillator { period } => write!(f, "oscillator of period {}", period),
+            Classification::Spaceship {
+                period,
+                displacement: (x, y),
+            } => write!(
+                f,
+                "spaceship moving ({}, {}) every {} generations",
+                x, y, period
+            ),
+            Classification::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// the cells of a generation, relative to the top left corner of their bounding box.
+type Shape = Vec<((i64, i64), u8)>;
+
+/// remembers the generations it has been shown, by their cells
+/// relative to the top left corner of their bounding box. only the generations
+/// up to the longest period it looks for are kept, so long runs do not fill the memory.
+#[derive(Debug, Clone)]
+pub struct Detector {
+    /// the generation a shape was last seen in, and where its corner was.
+    history: HashMap<Shape, (u64, (i64, i64))>,
+    /// the shapes in the order they were seen, to forget them once they are too old.
+    seen: VecDeque<(u64, Shape)>,
+    max_period: u64,
+}
+
+impl Default for Detector {
+    fn default() -> Detector {
+        Detector {
+            history: HashMap::new(),
+            seen: VecDeque::new(),
+            max_period: MAX_PERIOD,
+        }
+    }
+}
+
+impl Detector {
+    pub fn new() -> Detector {
+        Detector::default()
+    }
+
+    /// looks for periods up to `max_period` generations instead of [`MAX_PERIOD`].
+    pub fn with_max_period(self, max_period: u64) -> Detector {
+        Detector { max_period, ..self }
+    }
+
+    /// records the current generation of the engine, and classifies the pattern
+    /// if the same cells were seen in an earlier generation, moved or not.
+    pub fn observe(&mut self, engine: &dyn LifeEngine) -> Option<Classification> {
+        let Some(((min_x, min_y), _)) = engine.bounding_box() else {
+            return Some(Classification::Dead);
+        };
+        let mut cells: Shape = engine
+            .cells()
+            .map(|((x, y), state)| ((x - min_x, y - min_y), state))
+            .collect();
+        cells.sort_unstable();
+
+        let generation = engine.generation();
+        while let Some((seen, shape)) = self.seen.front() {
+            if seen.saturating_add(self.max_period) >= generation {
+                break;
+            }
+            // the shape may have come back since, and be remembered for that
+            if self.history.get(shape).map(|(last, _)| last) == Some(seen) {
+                self.history.remove(shape);
+            }
+            self.seen.pop_front();
+        }
+        self.seen.push_back((generation, cells.clone()));
+        let (seen, (x, y)) = self.history.insert(cells, (generation, (min_x, min_y)))?;
+        // the same generation shown twice, or an engine that was reset
+        let period = generation.checked_sub(seen).filter(|period| *period > 0)?;
+        Some(match ((min_x - x, min_y - y), period) {
+            ((0, 0), 1) => Classification::StillLife,
+            ((0, 0), period) => Classification::Oscillator { period },
+            (displacement, period) => Classification::Spaceship {
+                period,
+                displacement,
+            },
+        })
+    }
+
+    /// forgets everything seen so far, e.g. after cells were changed by hand.
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.seen.clear();
+    }
+}
+
+/// runs the engine until its pattern repeats, for at most `max_generations` generations.
+pub fn classify(engine: &mut dyn LifeEngine, max_generations: u64) -> Classification {
+    let mut detector = Detector::new();
+    for _ in 0..max_generations {
+        if let Some(classification) = detector.observe(engine) {
+            return classification;
+        }
+        engine.advance(1);
+    }
+    detector.observe(engine).unwrap_or(Classification::Unknown)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{classify, Classification, Detector};
+    use crate::algorythms::game_of_life::{
+        board::Board, pattern::Pattern, patterns::Patterns, universe::Universe,
+    };
+
+    fn run(pattern: &str) -> Classification {
+        classify_pattern(&pattern.parse().unwrap())
+    }
+
+    fn classify_pattern(pattern: &Pattern) -> Classification {
+        let mut universe = Universe::default();
+        universe.place_pattern(pattern);
+        classify(&mut universe, 1000)
+    }
+
+    #[test]
+    fn classifies() {
+        assert_eq!(run("2o$2o!"), Classification::StillLife);
+        assert_eq!(run("3o!"), Classification::Oscillator { period: 2 });
+        // the pulsar
+        assert_eq!(
+            run("2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+            Classification::Oscillator { period: 3 }
+        );
+        assert_eq!(
+            run("bo$2bo$3o!"),
+            Classification::Spaceship {
+                period: 4,
+                displacement: (1, 1)
+            }
+        );
+        assert_eq!(run("2o!"), Classification::Dead);
+        // the R-pentomino keeps going for 1103 generations
+        assert_eq!(run("b2o$2o$bo!"), Classification::Unknown);
+
+        let copperhead = classify_pattern(&Patterns::Copperhead.pattern());
+        assert_eq!(copperhead.period(), Some(10));
+        assert_eq!(copperhead.displacement(), Some((1, 0)));
+        assert_eq!(
+            copperhead.to_string(),
+            "spaceship moving (1, 0) every 10 generations"
+        );
+    }
+
+    #[test]
+    fn waits_for_a_repeat() {
+        let mut board = Board::new((0, 0), (5, 5));
+        board.fill();
+        board.place_pattern(&"$b3o!".parse().unwrap());
+        let mut detector = Detector::new();
+        assert_eq!(detector.observe(&board), None);
+        // looking twice at one generation tells nothing
+        assert_eq!(detector.observe(&board), None);
+        board.tick();
+        assert_eq!(detector.observe(&board), None);
+        board.tick();
+        assert_eq!(
+            detector.observe(&board),
+            Some(Classification::Oscillator { period: 2 })
+        );
+        assert_eq!(board.generation(), 2);
+        detector.clear();
+        assert_eq!(detector.observe(&board), None);
+
+        // a period longer than the detector looks for is not noticed
+        let mut detector = Detector::new().with_max_period(1);
+        for _ in 0..10 {
+            assert_eq!(detector.observe(&board), None);
+            board.tick();
+        }
+        assert!(detector.history.len() <= 2);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fmt::Display, str::FromStr};
+
+/// how the edges of a bounded grid are joined, as in Golly's bounded grids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Topology {
+    /// the edges are walls with dead cells behind them.
+    #[default]
+    Plane,
+    /// top joined to bottom and left to right.
+    Torus,
+    /// like a torus, but one pair of edges is joined with a twist,
+    /// the top and bottom edges if `twisted_rows`, the left and right ones otherwise.
+    KleinBottle { twisted_rows: bool },
+    /// both pairs of edges are joined with a twist.
+    CrossSurface,
+    /// the top edge is joined to the left one and the bottom edge to the right one.
+    /// only works on square grids, others behave like a plane.
+    Sphere,
+}
+
+impl Topology {
+    /// the cell a neighbour at `(x, y)` ends up on in a `width` by `height` grid,
+    /// with coordinates relative to its top left corner. `None` if it falls off a wall.
+    /// when a corner neighbour crosses two edges, the joins are followed one after another.
+    pub fn wrap(&self, (x, y): (i64, i64), (width, height): (i64, i64)) -> Option<(i64, i64)> {
+        let inside = |x: i64, y: i64| (0..width).contains(&x) && (0..height).contains(&y);
+        let (mut x, mut y) = (x, y);
+        match self {
+            Topology::Plane => {}
+            Topology::Torus => (x, y) = (x.rem_euclid(width), y.rem_euclid(height)),
+            Topology::KleinBottle { twisted_rows } => {
+                if !(0..height).contains(&y) && *twisted_rows {
+                    x = width - 1 - x;
+                }
+                if !(0..width).contains(&x) && !twisted_rows {
+                    y = height - 1 - y;
+                }
+                (x, y) = (x.rem_euclid(width), y.rem_euclid(height));
+            }
+            Topology::CrossSurface => {
+                if !(0..width).contains(&x) {
+                    (x, y) = (x.rem_euclid(width), height - 1 - y);
+                }
+                if !(0..height).contains(&y) {
+                    (x, y) = (width - 1 - x, y.rem_euclid(height));
+                }
+            }
+            Topology::Sphere if width == height => {
+                // crossing the top edge at column x comes back in through the left edge at row x
+                for _ in 0..2 {
+                    (x, y) = match (x, y) {
+                        (x, -1) => (0, x),
+                        (-1, y) => (y, 0),
+                        (x, y) if y == height => (width - 1, x),
+                        (x, y) if x == width => (y, height - 1),
+                        position => position,
+                    };
+                }
+            }
+            Topology::Sphere => {}
+        }
+        inside(x, y).then_some((x, y))
+    }
+}
+
+/// a bounded grid in Golly's notation, the part after the `:` in `B3/S23:T100,50`.
+/// a `*` marks the twisted edges of a Klein bottle, and has to be there:
+/// `K100*,50` twists the top and bottom edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Grid {
+    pub topology: Topology,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (width, height) = (self.width, self.height);
+        match self.topology {
+            Topology::Plane => write!(f, "P{},{}", width, height),
+            Topology::Torus => write!(f, "T{},{}", width, height),
+            Topology::KleinBottle { twisted_rows: true } => write!(f, "K{}*,{}", width, height),
+            Topology::KleinBottle {
+                twisted_rows: false,
+            } => write!(f, "K{},{}*", width, height),
+            Topology::CrossSurface => write!(f, "C{},{}", width, height),
+            Topology::Sphere => write!(f, "S{}", width),
+        }
+    }
+}
+
+impl FromStr for Grid {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Grid, String> {
+        let error = || format!("{} is not a bounded grid", text);
+        let text = text.trim().to_ascii_uppercase();
+        let mut chars = text.chars();
+        let (kind, size) = (chars.next(), chars.as_str());
+        let (width, height) = size.split_once(',').unwrap_or((size, size));
+        let twisted_rows = width.ends_with('*');
+        let twisted_columns = height.ends_with('*');
+        let width = width.trim_end_matches('*').parse().map_err(|_| error())?;
+        let height = height.trim_end_matches('*').parse().map_err(|_| error())?;
+        let topology = match (kind, twisted_rows, twisted_columns) {
+            (Some('P'), false, false) => Topology::Plane,
+            (Some('T'), false, false) => Topology::Torus,
+            (Some('K'), true, false) => Topology::KleinBottle { twisted_rows: true },
+            (Some('K'), false, true) => Topology::KleinBottle {
+                twisted_rows: false,
+            },
+            (Some('C'), false, false) => Topology::CrossSurface,
+            (Some('S'), false, false) if width == height => Topology::Sphere,
+            _ => return Err(error()),
+        };
+        Ok(Grid {
+            topology,
+            width,
+            height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Grid, Topology};
+
+    const SIZE: (i64, i64) = (5, 4);
+
+    #[test]
+    fn wraps() {
+        assert_eq!(Topology::Plane.wrap((-1, 0), SIZE), None);
+        assert_eq!(Topology::Plane.wrap((4, 3), SIZE), Some((4, 3)));
+        assert_eq!(Topology::Torus.wrap((-1, 4), SIZE), Some((4, 0)));
+
+        let klein = Topology::KleinBottle { twisted_rows: true };
+        assert_eq!(klein.wrap((1, -1), SIZE), Some((3, 3)));
+        assert_eq!(klein.wrap((5, 1), SIZE), Some((0, 1)));
+        let klein = Topology::KleinBottle {
+            twisted_rows: false,
+        };
+        assert_eq!(klein.wrap((5, 1), SIZE), Some((0, 2)));
+        assert_eq!(klein.wrap((1, 4), SIZE), Some((1, 0)));
+
+        assert_eq!(Topology::CrossSurface.wrap((1, -1), SIZE), Some((3, 3)));
+        assert_eq!(Topology::CrossSurface.wrap((-1, 0), SIZE), Some((4, 3)));
+
+        assert_eq!(Topology::Sphere.wrap((2, -1), (4, 4)), Some((0, 2)));
+        assert_eq!(Topology::Sphere.wrap((-1, 3), (4, 4)), Some((3, 0)));
+        assert_eq!(Topology::Sphere.wrap((1, 4), (4, 4)), Some((3, 1)));
+        assert_eq!(Topology::Sphere.wrap((4, 2), (4, 4)), Some((2, 3)));
+        assert_eq!(Topology::Sphere.wrap((2, -1), SIZE), None);
+    }
+
+    #[test]
+    fn golly_notation() {
+        for text in ["T100,50", "P10,10", "K4*,3", "K4,3*", "C7,2", "S9"] {
+            assert_eq!(
+                text.parse::<Grid>().map(|grid| grid.to_string()),
+                Ok(text.to_string())
+            );
+        }
+        assert_eq!(
+            "t30".parse(),
+            Ok(Grid {
+                topology: Topology::Torus,
+                width: 30,
+                height: 30
+            })
+        );
+        assert!("S4,5".parse::<Grid>().is_err());
+        assert!("K4*,5*".parse::<Grid>().is_err());
+        // a Klein bottle has to say which edges are twisted
+        assert!("K100,50".parse::<Grid>().is_err());
+        assert!("X4,5".parse::<Grid>().is_err());
+        assert!("T4,".parse::<Grid>().is_err());
+        assert!("é4".parse::<Grid>().is_err());
+        assert!("".parse::<Grid>().is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use super::{engine::LifeEngine, pattern::Pattern, rule::Rule};
+use std::collections::HashMap;
+
+/// an unbounded plane that only stores the cells that are not dead.
+/// coordinates are signed, so patterns can grow in every direction.
+/// rules with `B0` bring the whole plane to life and are refused, they only run on bounded grids.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Universe {
+    cells: HashMap<(i64, i64), u8>,
+    rule: Rule,
+    generation: u64,
+}
+
+impl Universe {
+    /// returns `None` for rules with `B0`.
+    pub fn new(rule: Rule) -> Option<Universe> {
+        (!rule.births(0)).then(|| Universe {
+            rule,
+            ..Default::default()
+        })
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// returns `None`, keeping the rule it has, for rules with `B0`.
+    pub fn set_rule(&mut self, rule: Rule) -> Option<()> {
+        (!rule.births(0)).then(|| self.rule = rule)
+    }
+
+    /// the number of generations computed since the universe was created.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// the state of a cell, 0 being dead and 1 alive.
+    pub fn get_cell(&self, position: (i64, i64)) -> u8 {
+        self.cells.get(&position).copied().unwrap_or(0)
+    }
+
+    pub fn set_cell(&mut self, position: (i64, i64), state: u8) {
+        if state >= self.rule.states() {
+            return;
+        }
+        if state == 0 {
+            self.cells.remove(&position);
+        } else {
+            self.cells.insert(position, state);
+        }
+    }
+
+    /// the cells that are not dead, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = ((i64, i64), u8)> + '_ {
+        self.cells
+            .iter()
+            .map(|(position, state)| (*position, *state))
+    }
+
+    /// the number of live cells, not counting dying ones.
+    pub fn population(&self) -> usize {
+        self.cells.values().filter(|state| **state == 1).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// the top left and bottom right cells of the smallest box around all cells that are not dead.
+    pub fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        let mut cells = self.cells.keys();
+        let first = *cells.next()?;
+        Some(cells.fold(
+            (first, first),
+            |((min_x, min_y), (max_x, max_y)), (x, y)| {
+                (
+                    (min_x.min(*x), min_y.min(*y)),
+                    (max_x.max(*x), max_y.max(*y)),
+                )
+            },
+        ))
+    }
+
+    /// adds the cells of a pattern at its position, or the origin.
+    /// if the pattern names a rule without `B0`, the universe switches to it.
+    pub fn place_pattern(&mut self, pattern: &Pattern) {
+        if let Some(rule) = pattern.parsed_rule() {
+            let _ = self.set_rule(rule);
+        }
+        for (position, state) in pattern.placed_cells() {
+            self.set_cell(position, state);
+        }
+    }
+
+    /// all cells that are not dead as a pattern, placed where they are in the universe.
+    pub fn to_pattern(&self) -> Pattern {
+        Pattern {
+            rule: Some(self.rule.to_string()),
+            ..Pattern::placed(self.cells().collect())
+        }
+    }
+
+    pub fn step(&mut self) {
+        let mut neighbours: HashMap<(i64, i64), u8> = HashMap::with_capacity(self.cells.len() * 8);
+        for ((x, y), _) in self.cells.iter().filter(|(_, state)| **state == 1) {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    // the edge of the i64 plane is a wall
+                    let (Some(nx), Some(ny)) = (x.checked_add(dx), y.checked_add(dy)) else {
+                        continue;
+                    };
+                    if (dx, dy) != (0, 0) {
+                        *neighbours.entry((nx, ny)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        // without B0, only cells next to live ones can be born
+        let mut next = HashMap::with_capacity(self.cells.len());
+        for (position, count) in &neighbours {
+            let state = self.rule.next(self.get_cell(*position), *count);
+            if state != 0 {
+                next.insert(*position, state);
+            }
+        }
+        for (position, state) in &self.cells {
+            if !neighbours.contains_key(position) {
+                let state = self.rule.next(*state, 0);
+                if state != 0 {
+                    next.insert(*position, state);
+                }
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    pub fn step_n(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// draws the bounding box of the cells, two rows per line, cut off at the size of the terminal.
+    pub fn fmt(&self) -> String {
+        self.render()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Universe;
+    use crate::algorythms::game_of_life::{pattern::Pattern, patterns::Patterns, rule::Rule};
+
+    #[test]
+    fn glider_travels() {
+        // a glider heading up and to the left, far into negative coordinates
+        let glider = "3o$o$bo!".parse::<Pattern>().unwrap();
+        let mut universe = Universe::default();
+        universe.place_pattern(&glider);
+        universe.step_n(4000);
+        assert_eq!(universe.generation(), 4000);
+        assert_eq!(universe.population(), 5);
+        assert_eq!(
+            universe.bounding_box(),
+            Some(((-1000, -1000), (-998, -998)))
+        );
+        assert_eq!(universe.to_pattern().cells, glider.cells);
+    }
+
+    #[test]
+    fn gun_keeps_firing() {
+        let mut universe = Universe::default();
+        universe.place_pattern(&Patterns::GosperGliderGun.pattern());
+        universe.step_n(30);
+        let population = universe.population();
+        // every 30 generations another glider leaves the gun
+        universe.step_n(300);
+        assert_eq!(universe.population(), population + 10 * 5);
+    }
+
+    #[test]
+    fn generations_and_cells() {
+        let mut universe = Universe::new("B2/S/C3".parse::<Rule>().unwrap()).unwrap();
+        universe.set_cell((0, 0), 1);
+        universe.set_cell((1, 0), 1);
+        universe.set_cell((i64::MAX, i64::MAX), 1);
+        universe.step();
+        assert_eq!(universe.get_cell((0, 0)), 2);
+        assert_eq!(universe.get_cell((0, -1)), 1);
+        assert_eq!(universe.population(), 4);
+        // there is no fourth state to set
+        universe.set_cell((5, 5), 3);
+        assert_eq!(universe.get_cell((5, 5)), 0);
+        universe.step();
+        assert_eq!(universe.get_cell((0, 0)), 0);
+        assert_eq!(universe.cells().filter(|(_, state)| *state == 2).count(), 4);
+
+        let mut blinker = Universe::default();
+        blinker.place_pattern(&"3o!".parse().unwrap());
+        assert_eq!(blinker.fmt(), "▀▀▀");
+        blinker.step();
+        blinker.set_cell((1, 0), 0);
+        assert_eq!(blinker.fmt(), "▀\n▀");
+        assert_eq!(Universe::default().fmt(), "");
+    }
+
+    #[test]
+    fn refuses_b0() {
+        let b0: Rule = "B013/S23".parse().unwrap();
+        assert_eq!(Universe::new(b0), None);
+        let mut universe = Universe::default();
+        assert_eq!(universe.set_rule(b0), None);
+        universe.place_pattern(&"x = 3, y = 1, rule = B013/S23\n3o!".parse().unwrap());
+        assert_eq!(universe.rule(), &Rule::conway());
+        assert_eq!(universe.population(), 3);
+        assert_eq!(universe.set_rule("B36/S23".parse().unwrap()), Some(()));
+    }
+}
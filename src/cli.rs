@@ -0,0 +1,276 @@
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+};
+use ttschnz::algorythms::{
+    cryptography::enigma::{
+        parse_rotors, Enigma, EnigmaReader, EnigmaSettings, KeySheet, ReflectorWiring, Trace,
+    },
+    game_of_life::census::SoupSearch,
+};
+
+const ENIGMA_USAGE: &str = "usage: ttschnz enigma [options] [file]
+
+encrypts the file (or stdin) and writes the result in capitals to stdout.
+characters the machine has no key for are dropped.
+
+options:
+    --model M3|M4        machine model, guessed from the number of rotors by default
+    --rotors I,II,III    rotors from left to right
+    --reflector B        A, B, C, B-thin or C-thin
+    --rings AAA          ring settings as letters or numbers (\"01 01 01\")
+    --pos AAA            starting positions as letters or numbers
+    --plugs \"AB CD\"      plugboard pairs
+    --uhr 0              route the 10 plug pairs through an Uhr at this position
+    --keysheet file      read reflector, rotors, rings and plugs from a key sheet
+    --day 12             the day of the key sheet to use
+    --interactive        draw the lamp board and light a lamp as every key is pressed";
+
+/// runs `ttschnz enigma`, `args` being everything after the subcommand.
+pub fn enigma(args: &[String]) -> Result<(), String> {
+    let mut options = args.iter();
+    let mut input = None;
+    let (mut model, mut rotors, mut reflector) = (None, None, None);
+    let (mut rings, mut positions, mut plugs, mut uhr) = (None, None, None, None);
+    let (mut keysheet, mut day) = (None, None);
+    let mut interactive = false;
+
+    while let Some(arg) = options.next() {
+        let mut value = || {
+            options
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value\n\n{}", arg, ENIGMA_USAGE))
+        };
+        match arg.as_str() {
+            "--model" => model = Some(value()?.parse().map_err(|e| format!("{}", e))?),
+            "--rotors" => rotors = Some(parse_rotors(&value()?).map_err(|e| e.to_string())?),
+            "--reflector" => {
+                let name = value()?;
+                reflector = Some(
+                    name.parse::<ReflectorWiring>()
+                        .map_err(|_| format!("unknown reflector {}", name))?,
+                )
+            }
+            "--rings" => rings = Some(value()?),
+            "--pos" => positions = Some(value()?),
+            "--plugs" => plugs = Some(value()?),
+            "--uhr" => {
+                let position = value()?;
+                uhr = Some(
+                    position
+                        .parse::<usize>()
+                        .map_err(|_| format!("{} is not an Uhr position", position))?,
+                )
+            }
+            "--keysheet" => keysheet = Some(value()?),
+            "--day" => {
+                let number = value()?;
+                day = Some(
+                    number
+                        .parse::<u32>()
+                        .map_err(|_| format!("{} is not a day", number))?,
+                )
+            }
+            "--interactive" => interactive = true,
+            "-h" | "--help" => {
+                println!("{}", ENIGMA_USAGE);
+                return Ok(());
+            }
+            file if !file.starts_with("--") && input.is_none() => input = Some(file.to_string()),
+            other => return Err(format!("unexpected argument {}\n\n{}", other, ENIGMA_USAGE)),
+        }
+    }
+
+    let mut settings = match (keysheet, day) {
+        (Some(path), Some(day)) => {
+            let sheet = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+            let sheet = sheet.parse::<KeySheet>().map_err(|e| e.to_string())?;
+            sheet.day(day).map_err(|e| e.to_string())?.clone()
+        }
+        (None, None) => EnigmaSettings::new(
+            rotors
+                .take()
+                .unwrap_or_else(|| parse_rotors("I,II,III").unwrap()),
+            reflector.take().unwrap_or(ReflectorWiring::B),
+        ),
+        _ => return Err("--keysheet and --day go together".to_string()),
+    };
+    // options given next to a key sheet take precedence over it
+    if let Some(rotors) = rotors {
+        settings.model = EnigmaSettings::new(rotors.clone(), settings.reflector).model;
+        settings.rotors = rotors;
+    }
+    if let Some(reflector) = reflector {
+        settings.reflector = reflector;
+    }
+    if let Some(model) = model {
+        settings.model = model;
+    }
+    if let Some(rings) = rings {
+        settings.rings = rings;
+    }
+    if let Some(positions) = positions {
+        settings.positions = positions;
+    }
+    if let Some(plugs) = plugs {
+        settings.plugs = plugs;
+    }
+    settings.uhr = uhr.or(settings.uhr);
+    let enigma = settings.build().map_err(|e| e.to_string())?;
+    if interactive {
+        return lamp_board(enigma);
+    }
+
+    let input: Box<dyn Read> = match input {
+        Some(path) => Box::new(File::open(&path).map_err(|e| format!("{}: {}", path, e))?),
+        None => Box::new(io::stdin()),
+    };
+    let mut output = String::new();
+    EnigmaReader::new(enigma, input)
+        .read_to_string(&mut output)
+        .map_err(|e| e.to_string())?;
+    let mut stdout = io::stdout();
+    writeln!(stdout, "{}", output.to_ascii_uppercase()).map_err(|e| e.to_string())
+}
+
+fn draw(enigma: &Enigma, trace: Option<&Trace>, typed: &str, lit: &str) {
+    let _ = clearscreen::clear();
+    let text = format!(
+        "{}\n\nin    {}\nout   {}\n\ntype to light the lamps, enter or escape quits\n",
+        enigma.fmt(trace),
+        typed,
+        lit
+    );
+    // in raw mode a line feed does not go back to the start of the line
+    print!("{}", text.replace('\n', "\r\n"));
+    let _ = io::stdout().flush();
+}
+
+/// types every key on the machine as it is pressed, redrawing the lamp board each time.
+/// the terminal is put in raw mode for that, and back when the user quits.
+fn lamp_board(mut enigma: Enigma) -> Result<(), String> {
+    terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+    let result = press_keys(&mut enigma);
+    let _ = terminal::disable_raw_mode();
+    result
+}
+
+fn press_keys(enigma: &mut Enigma) -> Result<(), String> {
+    let (mut typed, mut lit) = (String::new(), String::new());
+    draw(enigma, None, &typed, &lit);
+    loop {
+        let Event::Key(key) = event::read().map_err(|e| e.to_string())? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char(key) => {
+                let Some(trace) = enigma.trace(key) else {
+                    continue;
+                };
+                typed.push(key.to_ascii_uppercase());
+                lit.extend(trace.lamp().map(|lamp| lamp.to_ascii_uppercase()));
+                draw(enigma, Some(&trace), &typed, &lit);
+            }
+            _ => {}
+        }
+    }
+}
+
+const CENSUS_USAGE: &str = "usage: ttschnz census [options]
+
+runs random soups until they settle down and counts the objects they leave behind,
+by their apgcode.
+
+options:
+    --soups 100          the number of soups to run
+    --seed 0             the seed of the first soup, the next ones count up from it
+    --size 16            width and height of the soups, or e.g. 16x32
+    --density 0.5        the chance of a cell to start alive
+    --symmetry C1        mirror or turn the soups: C1, C2, C4, D2, D4 or D8,
+                         --size being the part that is copied
+    --rule B3/S23        a Life-like rule
+    --out census.txt     write the census to a file instead of stdout";
+
+/// runs `ttschnz census`, `args` being everything after the subcommand.
+pub fn census(args: &[String]) -> Result<(), String> {
+    let mut options = args.iter();
+    let mut search = SoupSearch::default();
+    let mut soups = 100;
+    let mut out = None;
+
+    while let Some(arg) = options.next() {
+        let mut value = || {
+            options
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value\n\n{}", arg, CENSUS_USAGE))
+        };
+        fn number<T: std::str::FromStr>(value: String) -> Result<T, String> {
+            value
+                .parse()
+                .map_err(|_| format!("{} is not a number", value))
+        }
+        match arg.as_str() {
+            "--soups" => soups = number(value()?)?,
+            "--seed" => search.seed = number(value()?)?,
+            "--size" => {
+                let size = value()?;
+                let (width, height) = size.split_once('x').unwrap_or((&size, &size));
+                search.width = number(width.to_string())?;
+                search.height = number(height.to_string())?;
+            }
+            "--density" => {
+                search.density = number(value()?)?;
+                if !(0.0..=1.0).contains(&search.density) {
+                    return Err(format!(
+                        "the density has to be between 0 and 1\n\n{}",
+                        CENSUS_USAGE
+                    ));
+                }
+            }
+            "--symmetry" => search.symmetry = value()?.parse().map_err(|e| format!("{}", e))?,
+            "--rule" => {
+                search.rule = value()?.parse().map_err(|e| format!("{}", e))?;
+                // apgcodes only name the objects of Life-like rules
+                if search.rule.states() > 2 {
+                    return Err(format!(
+                        "{} is not a Life-like rule\n\n{}",
+                        search.rule, CENSUS_USAGE
+                    ));
+                }
+                // soups run in the unbounded universe, which B0 would fill
+                if search.rule.births(0) {
+                    return Err(format!(
+                        "{} has B0, which the unbounded universe can not run\n\n{}",
+                        search.rule, CENSUS_USAGE
+                    ));
+                }
+            }
+            "--out" => out = Some(value()?),
+            "-h" | "--help" => {
+                println!("{}", CENSUS_USAGE);
+                return Ok(());
+            }
+            other => return Err(format!("unexpected argument {}\n\n{}", other, CENSUS_USAGE)),
+        }
+    }
+
+    let census = search.run(soups);
+    match out {
+        Some(path) => census.save(&path).map_err(|e| format!("{}: {}", path, e)),
+        None => {
+            print!("{}", census);
+            Ok(())
+        }
+    }
+}
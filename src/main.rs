@@ -1,18 +1,43 @@
-use clearscreen;
-use ttschnz::algorythms::game_of_life::{board::Board, patterns::Patterns};
+use ttschnz::algorythms::game_of_life::{board::Board, patterns::Patterns, stability::Detector};
+
+mod cli;
 
 fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if let Some((command, args)) = args.split_first() {
+        let result = match command.as_str() {
+            "enigma" => cli::enigma(args),
+            "census" => cli::census(args),
+            _ => Err(format!(
+                "unknown command {}, try `enigma` or `census`",
+                command
+            )),
+        };
+        if let Err(message) = result {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut board = Board::new((0, 0), (64, 64));
     board.fill();
     board.create_life(Patterns::GPT);
 
-    while board.has_live() {
+    let mut detector = Detector::new();
+    let classification = loop {
         let output = board.fmt();
         let _ = clearscreen::clear();
         println!("{}", output);
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        if !board.tick() {
-            break;
+        if let Some(classification) = detector.observe(&board) {
+            break classification;
         }
-    }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        board.tick();
+    };
+    println!(
+        "{} after {} generations",
+        classification,
+        board.generation()
+    );
 }